@@ -0,0 +1,270 @@
+/*
+ * Copyright 2015 MaidSafe.net limited.
+ *
+ * This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+ * version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+ * licence you accepted on initial access to the Software (the "Licences").
+ *
+ * By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+ * bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+ * Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+ *
+ * Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+ * under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.
+ *
+ * Please review the Licences for the specific language governing permissions and limitations
+ * relating to use of the SAFE Network Software.
+ */
+
+/*
+ * C interface to the safe_ffi library, generated from the functions it exports by the test in
+ * src/c_header.rs. Do not edit this file by hand: after changing an exported function or an error
+ * code, run `SAFE_FFI_WRITE_HEADER=1 cargo test c_header` to regenerate it.
+ *
+ * Functions returning int32_t return 0 on success and one of the error codes on failure.
+ */
+
+#ifndef SAFE_FFI_H
+#define SAFE_FFI_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Error codes owned by safe_ffi. Codes above SAFE_FFI_ERROR_START_RANGE come from the DNS, NFS
+ * and core modules of safe_core. */
+#define SAFE_FFI_ERROR_START_RANGE -1500
+#define SAFE_FFI_ERROR_PATH_NOT_FOUND (SAFE_FFI_ERROR_START_RANGE - 1)
+#define SAFE_FFI_ERROR_INVALID_PATH (SAFE_FFI_ERROR_START_RANGE - 2)
+#define SAFE_FFI_ERROR_PERMISSION_DENIED (SAFE_FFI_ERROR_START_RANGE - 3)
+#define SAFE_FFI_ERROR_JSON_PARSE_ERROR (SAFE_FFI_ERROR_START_RANGE - 4)
+#define SAFE_FFI_ERROR_JSON_DECODE_ERROR (SAFE_FFI_ERROR_START_RANGE - 5)
+#define SAFE_FFI_ERROR_SPECIFIC_PARSE_ERROR (SAFE_FFI_ERROR_START_RANGE - 6)
+#define SAFE_FFI_ERROR_JSON_ENCODE_ERROR (SAFE_FFI_ERROR_START_RANGE - 7)
+#define SAFE_FFI_ERROR_LOCAL_CONFIG_ACCESS_FAILED (SAFE_FFI_ERROR_START_RANGE - 8)
+#define SAFE_FFI_ERROR_UNEXPECTED (SAFE_FFI_ERROR_START_RANGE - 9)
+#define SAFE_FFI_ERROR_UNSUCCESSFUL_ENCODE_DECODE (SAFE_FFI_ERROR_START_RANGE - 10)
+#define SAFE_FFI_ERROR_REQUEST_CANCELLED (SAFE_FFI_ERROR_START_RANGE - 11)
+#define SAFE_FFI_ERROR_INVALID_REQUEST_ID (SAFE_FFI_ERROR_START_RANGE - 12)
+#define SAFE_FFI_ERROR_VERSION_NOT_FOUND (SAFE_FFI_ERROR_START_RANGE - 13)
+#define SAFE_FFI_ERROR_INVALID_PATH_SEGMENT (SAFE_FFI_ERROR_START_RANGE - 14)
+#define SAFE_FFI_ERROR_ROLLBACK_FAILED (SAFE_FFI_ERROR_START_RANGE - 15)
+#define SAFE_FFI_ERROR_CALLED_FROM_CALLBACK (SAFE_FFI_ERROR_START_RANGE - 16)
+
+/* Codes passed to the callbacks registered via register_network_event_observer */
+#define SAFE_FFI_NETWORK_EVENT_CONNECTED 0
+#define SAFE_FFI_NETWORK_EVENT_DISCONNECTED 1
+#define SAFE_FFI_NETWORK_EVENT_TERMINATED 2
+
+/* Invoked with the code of each network event, such as Connected or Disconnected, after
+ * registration via `register_network_event_observer`. */
+typedef void (*NetworkEventObserver)(int32_t);
+
+/* Invoked on completion of an asynchronous request with the user data given on submission, the
+ * result code and the response bytes along with their size. */
+typedef void (*CompletionCallback)(void*, int32_t, const uint8_t*, int32_t);
+
+/* This function should be called to enable logging to a file */
+int32_t init_logging(void);
+
+/* Create an unregistered client. This or any one of the other companion functions to get a
+ * client must be called before initiating any operation allowed by this crate. */
+int32_t create_unregistered_client(const void** ffi_handle);
+
+/* Create a registered client. This or any one of the other companion functions to get a
+ * client must be called before initiating any operation allowed by this crate. `client_handle` is
+ * a pointer to a pointer and must point to a valid pointer not junk, else the consequences are
+ * undefined. */
+int32_t create_account(const char* c_keyword,
+                       const char* c_pin,
+                       const char* c_password,
+                       const void** ffi_handle);
+
+/* Log into a registered client. This or any one of the other companion functions to get a
+ * client must be called before initiating any operation allowed by this crate. `client_handle` is
+ * a pointer to a pointer and must point to a valid pointer not junk, else the consequences are
+ * undefined. */
+int32_t log_in(const char* c_keyword,
+               const char* c_pin,
+               const char* c_password,
+               const void** ffi_handle);
+
+/* Register an observer to network events like Connected, Disconnected etc. as provided by the
+ * core module */
+void register_network_event_observer(const void* handle, NetworkEventObserver callback);
+
+/* Returns key size */
+const uint8_t* get_app_dir_key(const char* c_app_name,
+                               const char* c_app_id,
+                               const char* c_vendor,
+                               int32_t* c_size,
+                               int32_t* c_capacity,
+                               int32_t* c_result,
+                               const void* ffi_handle);
+
+/* Returns Key as base64 string */
+const uint8_t* get_safe_drive_key(int32_t* c_size,
+                                  int32_t* c_capacity,
+                                  int32_t* c_result,
+                                  const void* ffi_handle);
+
+/* Discard and clean up the previously allocated client. Use this only if the client is obtained
+ * from one of the client obtainment functions in this crate (`crate_account`, `log_in`,
+ * `create_unregistered_client`). Using `client_handle` after a call to this functions is
+ * undefined behaviour. Dropping the client waits for the callbacks of its asynchronous requests
+ * to return, so when called from within one of them the client is not dropped and the error
+ * code for a call from a callback is returned instead. */
+int32_t drop_client(const void* client_handle);
+
+/* General function that can be invoked for performing a API specific operation that will return
+ * only result to indicate whether the operation was successful or not.
+ * This function would only perform the operation and return 0 or error code
+ * c_payload refers to the JSON payload that can be passed as a JSON string.
+ * The JSON string should have keys module, action, app_root_dir_key, safe_drive_dir_key,
+ * safe_drive_access and data. `data` refers to API specific payload. */
+int32_t execute(const char* c_payload, const void* ffi_handle);
+
+/* General function that can be invoked for getting data as a resut for an operation.
+ * The function return a pointer to a U8 vecotr. The size of the U8 vector and its capacity is
+ * written to the out params c_size & c_capacity. The size and capcity would be required for
+ * droping the vector The result of the execution is returned in the c_result out param */
+const uint8_t* execute_for_content(const char* c_payload,
+                                   int32_t* c_size,
+                                   int32_t* c_capacity,
+                                   int32_t* c_result,
+                                   const void* ffi_handle);
+
+/* Asynchronous variant of `execute` and `execute_for_content`. c_payload is the same JSON payload
+ * these functions take. The request is queued to a pool of worker threads sharing the client and
+ * this function returns immediately, writing the id of the request to `c_request_id`. On
+ * completion `callback` is invoked from one of the worker threads with `user_data`, the result
+ * code and the response bytes along with their size. The response bytes are only valid for the
+ * duration of the callback. `drop_client` fails if called from within the callback. */
+int32_t execute_async(const char* c_payload,
+                      void* user_data,
+                      CompletionCallback callback,
+                      uint64_t* c_request_id,
+                      const void* ffi_handle);
+
+/* Cancel a request submitted via `execute_async`. Only a request which is still queued can be
+ * cancelled; its callback is then invoked with the error code for a cancelled request. A request
+ * which is already being executed runs to completion. */
+int32_t cancel_async_request(uint64_t request_id, const void* ffi_handle);
+
+/* Configure the cache of the directory listings fetched while resolving the paths of requests
+ * made with `ffi_handle`. At most `capacity` listings are cached, each for at most
+ * `time_to_live_secs` seconds, after which it is fetched from the network again. Changes made
+ * through this crate invalidate the listings they affect, but changes made by other clients are
+ * only seen once the cached listings expire. The cache is disabled by default and a `capacity` of
+ * 0 disables it again. */
+int32_t configure_directory_cache(uint64_t capacity,
+                                  uint64_t time_to_live_secs,
+                                  const void* ffi_handle);
+
+/* Returns the configuration and usage of the directory cache of `ffi_handle` as a JSON string
+ * with the keys capacity, time_to_live_secs, size, hits and misses. The vector is returned as for
+ * execute_for_content and must be released with drop_vector. */
+const uint8_t* get_directory_cache_stats(int32_t* c_size,
+                                         int32_t* c_capacity,
+                                         int32_t* c_result,
+                                         const void* ffi_handle);
+
+/* Start a local HTTP server previewing the sites published through DNS, using the client of
+ * `ffi_handle`. It binds to `port` on the loopback interface, or to a free port if `port` is 0,
+ * and writes the port it bound to to `c_port`. `http://service.long_name/path` is served as
+ * `safe://service.long_name/path` resolves with the `resolve` action of the `dns` module, and
+ * the site may instead be given as the first segment of the path, as in
+ * `http://localhost:port/service.long_name/path`. Only one server runs per handle. */
+int32_t start_http_gateway(uint16_t port, uint16_t* c_port, const void* ffi_handle);
+
+/* Stop the HTTP server started with `start_http_gateway`, if any. The server is also stopped by
+ * `drop_client`. */
+int32_t stop_http_gateway(const void* ffi_handle);
+
+/* Drop the vector returned as a result of the execute_for_content fn */
+void drop_vector(uint8_t* ptr, int32_t size, int32_t capacity);
+
+/* Drop the null pointer returned as error from the execute_for_content fn */
+void drop_null_ptr(uint8_t* ptr);
+
+/* Open a reader over the content of a file. c_payload is a JSON string with the keys
+ * app_dir_key, safe_drive_dir_key, safe_drive_access and data, where `data` holds file_path and
+ * is_path_shared exactly as for the nfs get-file action. The file content is never copied into
+ * JSON; it is read piece by piece via `read_from_file_reader`. The reader must be released with
+ * `close_file_reader`. */
+int32_t open_file_reader(const char* c_payload, const void* ffi_handle, const void** reader_handle);
+
+/* Returns the size of the file opened by `open_file_reader` */
+uint64_t get_file_reader_size(const void* reader_handle);
+
+/* Read at most `length` bytes starting at `offset` into `c_buffer`, which must be allocated by
+ * the caller to hold at least `length` bytes. The number of bytes actually read, which is less
+ * than `length` only at the end of the file, is written to `c_read_size`. */
+int32_t read_from_file_reader(const void* reader_handle,
+                              uint64_t offset,
+                              uint64_t length,
+                              uint8_t* c_buffer,
+                              uint64_t* c_read_size);
+
+/* Discard a reader obtained from `open_file_reader`. Using `reader_handle` after a call to this
+ * function is undefined behaviour. */
+void close_file_reader(const void* reader_handle);
+
+/* Open a writer over the content of an existing file. c_payload is a JSON string with the keys
+ * app_dir_key, safe_drive_dir_key, safe_drive_access and data, where `data` holds file_path,
+ * is_path_shared and mode. `mode` is either "overwrite" to replace the content or "modify" to
+ * change it in place. Raw bytes are then sent via `write_to_file_writer`. The directory listing
+ * of the file is updated only when the writer is committed with `commit_file_writer`;
+ * `abort_file_writer` discards everything written. */
+int32_t open_file_writer(const char* c_payload, const void* ffi_handle, const void** writer_handle);
+
+/* Write `length` bytes from `c_data` at `offset` of the file opened by `open_file_writer` */
+void write_to_file_writer(const void* writer_handle,
+                          const uint8_t* c_data,
+                          uint64_t length,
+                          uint64_t offset);
+
+/* Commit everything written via the writer and update the directory listing of the file. The
+ * writer is released whether or not the commit succeeds, so using `writer_handle` after a call to
+ * this function is undefined behaviour. */
+int32_t commit_file_writer(const void* writer_handle);
+
+/* Discard a writer obtained from `open_file_writer` along with everything written through it.
+ * Using `writer_handle` after a call to this function is undefined behaviour. */
+void abort_file_writer(const void* writer_handle);
+
+/* Returns the details of the error of the last failed call made with `ffi_handle` as a JSON string
+ * with the keys category, code, description, field and path. `field` is the JSON field missing
+ * from or malformed in the request and `path` the path the request operated on; either is null
+ * when not known. Calls which succeed leave the details untouched, so this should be called right
+ * after the call which failed. Errors of asynchronous requests are only reported to their
+ * callbacks. The vector, which is empty if no call has failed yet, is returned as for
+ * execute_for_content and must be released with drop_vector. */
+const uint8_t* get_last_error(int32_t* c_size,
+                              int32_t* c_capacity,
+                              int32_t* c_result,
+                              const void* ffi_handle);
+
+/* Returns the name, owning module and description of `code`, which may be any code returned by
+ * this crate, as a JSON string with the keys code, name, module and description. The vector is
+ * returned as for execute_for_content and must be released with drop_vector. */
+const uint8_t* describe_error_code(int32_t code,
+                                   int32_t* c_size,
+                                   int32_t* c_capacity,
+                                   int32_t* c_result);
+
+/* Returns the table of all the error codes of this crate, as a JSON array of objects with the
+ * keys code, name, module and description, for bindings to generate their error types from.
+ * Codes of safe_core which are not listed individually can still be looked up with
+ * `describe_error_code`. The vector is returned as for execute_for_content and must be released
+ * with drop_vector. */
+const uint8_t* get_error_codes(int32_t* c_size, int32_t* c_capacity, int32_t* c_result);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SAFE_FFI_H */
@@ -0,0 +1,214 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use std::collections::BTreeMap;
+
+use errors::{ErrorDetails, FfiError};
+use rustc_serialize::json::{self, Json};
+use {helper, ParameterPacket, ResponseType, Action};
+
+/// Executes the requests of a batch in order, all with the keys and access given to the batch
+/// itself, and replies with the result or the error of each. Keys given in the requests are
+/// ignored and batches can not be nested. If `stop_on_failure` is set, the requests following the
+/// first failed one are not executed and have no entry in the reply.
+#[derive(Debug)]
+pub struct Execute {
+    requests: Vec<Json>,
+    stop_on_failure: bool,
+}
+
+impl Execute {
+    pub fn from_request(request: Json) -> Result<Execute, FfiError> {
+        let mut data = match try!(take_field(&mut try!(into_object(request)), "data")) {
+            Json::Object(data) => data,
+            data => return Err(get_expected_error("Object", &data)),
+        };
+        let requests = match try!(take_field(&mut data, "requests")) {
+            Json::Array(requests) => requests,
+            requests => return Err(get_expected_error("Array", &requests)),
+        };
+        let stop_on_failure = match data.remove("stop_on_failure") {
+            Some(Json::Null) | None => false,
+            Some(Json::Boolean(stop_on_failure)) => stop_on_failure,
+            Some(stop_on_failure) => return Err(get_expected_error("Boolean", &stop_on_failure)),
+        };
+
+        Ok(Execute {
+            requests: requests,
+            stop_on_failure: stop_on_failure,
+        })
+    }
+}
+
+impl Action for Execute {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let mut results = Vec::with_capacity(self.requests.len());
+        for request in self.requests.drain(..) {
+            let mut result = BTreeMap::new();
+            let is_failed = match execute_request(request, params.clone()) {
+                Ok(response) => {
+                    // Replies are JSON themselves and are nested as such rather than as strings
+                    let response = response.map(|response| {
+                        Json::from_str(&response).unwrap_or(Json::String(response))
+                    });
+                    let _ = result.insert("result".to_string(), response.unwrap_or(Json::Null));
+                    false
+                }
+                Err(error) => {
+                    let details = ErrorDetails::from(error);
+                    let details = try!(Json::from_str(&try!(json::encode(&details))));
+                    let _ = result.insert("error".to_string(), details);
+                    true
+                }
+            };
+            results.push(Json::Object(result));
+
+            if is_failed && self.stop_on_failure {
+                break;
+            }
+        }
+
+        let mut response = BTreeMap::new();
+        let _ = response.insert("results".to_string(), Json::Array(results));
+        Ok(Some(try!(json::encode(&Json::Object(response)))))
+    }
+}
+
+fn execute_request(request: Json, params: ParameterPacket) -> ResponseType {
+    let module = try!(get_string(&request, "module"));
+    let action = try!(get_string(&request, "action"));
+    if module == "batch" {
+        return Err(FfiError::SpecificParseError("Batches can not be nested".to_string())
+                       .with_field("module".to_string()));
+    }
+    // A panic fails only the request causing it rather than the whole batch
+    try!(helper::catch_unwind(|| {
+        ::module_parser(module, action, params, request)
+    }))
+}
+
+fn into_object(json: Json) -> Result<json::Object, FfiError> {
+    match json {
+        Json::Object(object) => Ok(object),
+        json => Err(get_expected_error("Object", &json)),
+    }
+}
+
+// Takes the value of `field` out of `object`, erring as the decoder would if it is absent
+fn take_field(object: &mut json::Object, field: &str) -> Result<Json, FfiError> {
+    match object.remove(field) {
+        Some(Json::Null) | None => Err(get_missing_field_error(field)),
+        Some(value) => Ok(value),
+    }
+}
+
+fn get_string(json: &Json, field: &str) -> Result<String, FfiError> {
+    match json.find(field) {
+        Some(&Json::String(ref value)) => Ok(value.clone()),
+        Some(&Json::Null) | None => Err(get_missing_field_error(field)),
+        Some(value) => Err(get_expected_error("String", value)),
+    }
+}
+
+fn get_missing_field_error(field: &str) -> FfiError {
+    FfiError::JsonDecodeError(json::DecoderError::MissingFieldError(field.to_string()))
+}
+
+fn get_expected_error(expected: &str, found: &Json) -> FfiError {
+    FfiError::JsonDecodeError(json::DecoderError::ExpectedError(expected.to_string(),
+                                                                found.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {Action, test_utils};
+    use rustc_serialize::json::Json;
+
+    fn get_batch(stop_on_failure: bool) -> Execute {
+        let requests = r#"[
+            {
+                "module": "nfs",
+                "action": "create-dir",
+                "data": {
+                    "dir_path": "/batch_dir",
+                    "is_private": true,
+                    "is_versioned": false,
+                    "user_metadata": "",
+                    "is_path_shared": false
+                }
+            },
+            {
+                "module": "nfs",
+                "action": "get-dir",
+                "data": {
+                    "dir_path": "/missing_dir",
+                    "is_path_shared": false
+                }
+            },
+            {
+                "module": "nfs",
+                "action": "get-dir",
+                "data": {
+                    "dir_path": "/batch_dir",
+                    "is_path_shared": false
+                }
+            }
+        ]"#;
+        let request = format!("{{\"data\": {{\"requests\": {}, \"stop_on_failure\": {}}}}}",
+                              requests,
+                              stop_on_failure);
+        unwrap_result!(Execute::from_request(unwrap_result!(Json::from_str(&request))))
+    }
+
+    fn get_results(batch: &mut Execute) -> Vec<Json> {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        let response = unwrap_option!(unwrap_result!(batch.execute(parameter_packet)), "");
+        let response = unwrap_result!(Json::from_str(&response));
+        unwrap_option!(response.find("results").and_then(|results| results.as_array()), "")
+            .clone()
+    }
+
+    #[test]
+    fn execute_batch() {
+        let results = get_results(&mut get_batch(false));
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].find("result"), Some(&Json::Null));
+        assert_eq!(results[1].find_path(&["error", "path"]).and_then(|path| path.as_string()),
+                   Some("/missing_dir"));
+        assert!(results[2].find_path(&["result", "info"]).is_some());
+
+        let results = get_results(&mut get_batch(true));
+        assert_eq!(results.len(), 2);
+        assert!(results[1].find("error").is_some());
+    }
+
+    #[test]
+    fn malformed_batch() {
+        assert!(Execute::from_request(unwrap_result!(Json::from_str("{\"data\": {}}"))).is_err());
+
+        // Requests lacking their module or action, and a nested batch, fail on their own
+        let request = r#"{"data": {"requests": [{"module": "nfs"},
+                                                {"action": "get-dir"},
+                                                {"module": "batch", "action": "execute"}]}}"#;
+        let request = unwrap_result!(Json::from_str(request));
+        let mut batch = unwrap_result!(Execute::from_request(request));
+        let results = get_results(&mut batch);
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|result| result.find("error").is_some()));
+    }
+}
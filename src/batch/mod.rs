@@ -0,0 +1,88 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use errors::FfiError;
+use helper::{self, ActionDecoder};
+use meta::schema::{ActionSchema, FieldSchema, optional, required};
+use meta::schema::FieldType::{Array, Bool, Object};
+use meta::schema::FieldType::String as Text;
+use rustc_serialize::json::Json;
+
+mod execute;
+
+pub fn action_dispatcher(action: String,
+                         params: ::ParameterPacket,
+                         request: &Json)
+                         -> ::ResponseType {
+    let mut action = try!(get_action(action, request));
+    action.execute(params)
+}
+
+/// Describes the actions of this module
+pub fn get_action_schemas() -> Vec<ActionSchema> {
+    get_actions().into_iter().map(|(schema, _)| schema).collect()
+}
+
+// Table of the actions of this module, which both their schemas and the decoding of requests are
+// taken from. Unlike the actions of the other modules a batch is decoded from its JSON rather
+// than by a decoder, as the `data` of each of its requests can only be decoded once the module
+// and action of the request are known.
+fn get_actions() -> Vec<(ActionSchema, ActionDecoder)> {
+    let decode_execute: ActionDecoder = decode_execute;
+    vec![(ActionSchema::new("execute",
+                            vec![required("requests",
+                                          Array(vec![required("module", Text),
+                                                     required("action", Text),
+                                                     optional("data", Object(Vec::new()))])),
+                                 optional("stop_on_failure", Bool)]),
+          decode_execute)]
+}
+
+fn decode_execute(request: &Json, _: &[FieldSchema]) -> Result<Box<::Action>, FfiError> {
+    Ok(Box::new(try!(execute::Execute::from_request(request.clone()))))
+}
+
+fn get_action(action: String, request: &Json) -> Result<Box<::Action>, FfiError> {
+    helper::find_action(get_actions(), action, request)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::get_action;
+    use meta::schema;
+
+    #[test]
+    fn action_schemas_match_decoding() {
+        for action in get_action_schemas() {
+            for &include_optional in &[false, true] {
+                let request = schema::get_sample_request(&action.fields, include_optional, None);
+                assert!(get_action(action.name.to_string(), &request).is_ok(),
+                        "{} fails to decode its described fields",
+                        action.name);
+            }
+
+            for field in action.fields.iter().filter(|field| field.is_required) {
+                let request = schema::get_sample_request(&action.fields, true, Some(field.name));
+                assert!(get_action(action.name.to_string(), &request).is_err(),
+                        "{} decodes without its required field {}",
+                        action.name,
+                        field.name);
+            }
+        }
+    }
+}
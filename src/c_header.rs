@@ -0,0 +1,323 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+//! Generator of `include/safe_ffi.h`. The header is built from the functions and function pointer
+//! types exported by the crate, as parsed by `syn`, along with the error codes and the network
+//! event codes, and the test in this module fails whenever the checked-in header differs from the
+//! generated one. Running the test with the environment variable `SAFE_FFI_WRITE_HEADER` set
+//! writes the generated header instead.
+
+use std::fs;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use errors::{self, FFI_ERROR_START_RANGE};
+use safe_core::core::translated_events::NetworkEvent;
+use syn::{Abi, Attribute, FnArg, Item, Lit, Meta, Pat, ReturnType, Type, Visibility};
+
+const HEADER_PATH: &'static str = "include/safe_ffi.h";
+const MAX_LINE_LENGTH: usize = 100;
+
+const INTRODUCTION: &'static str =
+    "/*
+ * C interface to the safe_ffi library, generated from the functions it exports by the test in
+ * src/c_header.rs. Do not edit this file by hand: after changing an exported function or an error
+ * code, run `SAFE_FFI_WRITE_HEADER=1 cargo test c_header` to regenerate it.
+ *
+ * Functions returning int32_t return 0 on success and one of the error codes on failure.
+ */";
+
+const ERROR_CODES_COMMENT: &'static str =
+    "/* Error codes owned by safe_ffi. Codes above SAFE_FFI_ERROR_START_RANGE come from the DNS, NFS
+ * and core modules of safe_core. */";
+
+/// Returns the content `include/safe_ffi.h` should have.
+pub fn generate_header() -> String {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    let mut typedefs = Vec::new();
+    let mut declarations = Vec::new();
+    for source_path in get_source_paths(&root.join("src")) {
+        let mut source = String::new();
+        let _ = unwrap_result!(unwrap_result!(File::open(&source_path))
+                                   .read_to_string(&mut source));
+        let file = unwrap_result!(::syn::parse_file(&source));
+
+        for item in file.items {
+            match item {
+                Item::Type(ref item) if is_public(&item.vis) => {
+                    if let Type::BareFn(ref function) = *item.ty {
+                        if is_c_abi(&function.abi) {
+                            let name = item.ident.to_string();
+                            let params = function.inputs
+                                                 .iter()
+                                                 .map(|param| get_c_type(&param.ty, &name))
+                                                 .collect::<Vec<_>>();
+                            let return_type = get_c_return_type(&function.output, &name);
+                            let declarator = format!("typedef {} (*{})(", return_type, name);
+                            typedefs.push(format!("{}{}",
+                                                  get_comment(&item.attrs),
+                                                  get_declaration(&declarator, &params)));
+                        }
+                    }
+                }
+                Item::Fn(ref item) if is_public(&item.vis) && is_c_abi(&item.sig.abi) &&
+                                      is_no_mangle(&item.attrs) => {
+                    let name = item.sig.ident.to_string();
+                    let params = item.sig
+                                     .inputs
+                                     .iter()
+                                     .map(|param| get_c_param(param, &name))
+                                     .collect::<Vec<_>>();
+                    let return_type = get_c_return_type(&item.sig.output, &name);
+                    let declarator = format!("{} {}(", return_type, name);
+                    declarations.push(format!("{}{}",
+                                              get_comment(&item.attrs),
+                                              get_declaration(&declarator, &params)));
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let mut license = String::from("/*\n");
+    for line in include_str!("lib.rs")
+                    .lines()
+                    .take_while(|line| line.starts_with("//") && !line.starts_with("//!")) {
+        license.push_str(&format!(" *{}\n", &line[2..]));
+    }
+    license.push_str(" */");
+
+    let mut sections = vec![license,
+                            INTRODUCTION.to_string(),
+                            "#ifndef SAFE_FFI_H\n#define SAFE_FFI_H".to_string(),
+                            "#include <stdint.h>".to_string(),
+                            "#ifdef __cplusplus\nextern \"C\" {\n#endif".to_string(),
+                            get_error_defines(),
+                            get_network_event_defines()];
+    sections.extend(typedefs);
+    sections.extend(declarations);
+    sections.push("#ifdef __cplusplus\n}\n#endif".to_string());
+    sections.push("#endif /* SAFE_FFI_H */".to_string());
+
+    sections.join("\n\n") + "\n"
+}
+
+// Every Rust source file under `directory`, with lib.rs first and the rest sorted by path
+fn get_source_paths(directory: &Path) -> Vec<PathBuf> {
+    fn collect(directory: &Path, paths: &mut Vec<PathBuf>) {
+        for entry in unwrap_result!(fs::read_dir(directory)) {
+            let path = unwrap_result!(entry).path();
+            if path.is_dir() {
+                collect(&path, paths);
+            } else if path.extension().map_or(false, |extension| extension == "rs") {
+                paths.push(path);
+            }
+        }
+    }
+
+    let mut paths = Vec::new();
+    collect(directory, &mut paths);
+    let lib = directory.join("lib.rs");
+    paths.sort_by_key(|path| (*path != lib, path.clone()));
+    paths
+}
+
+fn is_public(visibility: &Visibility) -> bool {
+    match *visibility {
+        Visibility::Public(_) => true,
+        _ => false,
+    }
+}
+
+fn is_c_abi(abi: &Option<Abi>) -> bool {
+    abi.as_ref()
+       .and_then(|abi| abi.name.as_ref())
+       .map_or(false, |name| name.value() == "C")
+}
+
+fn is_no_mangle(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path.is_ident("no_mangle"))
+}
+
+// Doc comment of an item as a C comment, followed by a new line, or nothing if it has none
+fn get_comment(attrs: &[Attribute]) -> String {
+    let lines = attrs.iter()
+                     .filter(|attr| attr.path.is_ident("doc"))
+                     .filter_map(|attr| match attr.parse_meta() {
+                         Ok(Meta::NameValue(ref meta)) => {
+                             match meta.lit {
+                                 Lit::Str(ref doc) => Some(doc.value()),
+                                 _ => None,
+                             }
+                         }
+                         _ => None,
+                     })
+                     .collect::<Vec<_>>();
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    // `/// text` is documented as " text"
+    let mut comment = String::from("/*");
+    for (index, line) in lines.iter().enumerate() {
+        if index > 0 {
+            comment.push_str("\n *");
+        }
+        comment.push_str(line);
+    }
+    comment.push_str(" */\n");
+    comment
+}
+
+// `declarator` followed by the parameters and the closing parenthesis, with the parameters on one
+// line each, aligned, if they do not fit on the line of the declarator
+fn get_declaration(declarator: &str, params: &[String]) -> String {
+    let params = if params.is_empty() {
+        vec!["void".to_string()]
+    } else {
+        params.to_vec()
+    };
+
+    let declaration = format!("{}{});", declarator, params.join(", "));
+    if declaration.len() <= MAX_LINE_LENGTH {
+        return declaration;
+    }
+
+    let separator = format!(",\n{}", " ".repeat(declarator.len()));
+    format!("{}{});", declarator, params.join(&separator))
+}
+
+fn get_c_param(param: &FnArg, function: &str) -> String {
+    match *param {
+        FnArg::Typed(ref param) => {
+            match *param.pat {
+                Pat::Ident(ref name) => {
+                    format!("{} {}", get_c_type(&param.ty, function), name.ident)
+                }
+                _ => panic!("Parameter of {} without a name", function),
+            }
+        }
+        FnArg::Receiver(_) => panic!("{} exported as a method", function),
+    }
+}
+
+fn get_c_return_type(output: &ReturnType, function: &str) -> String {
+    match *output {
+        ReturnType::Default => "void".to_string(),
+        ReturnType::Type(_, ref return_type) => get_c_type(return_type, function),
+    }
+}
+
+// Spells `rust_type`, used in the signature of `function`, in C
+fn get_c_type(rust_type: &Type, function: &str) -> String {
+    match *rust_type {
+        Type::Ptr(ref pointer) => {
+            let pointee = get_c_type(&pointer.elem, function);
+            if pointer.mutability.is_some() {
+                format!("{}*", pointee)
+            } else if pointee.ends_with('*') {
+                format!("{} const*", pointee)
+            } else {
+                format!("const {}*", pointee)
+            }
+        }
+        Type::Path(ref path) => {
+            let name = unwrap_option!(path.path.segments.last(),
+                                      format!("Empty type in the signature of {}", function))
+                           .ident
+                           .to_string();
+            match &name[..] {
+                "c_void" => "void".to_string(),
+                "c_char" => "char".to_string(),
+                "u8" | "uint8_t" => "uint8_t".to_string(),
+                "i32" | "int32_t" => "int32_t".to_string(),
+                "u16" | "uint16_t" => "uint16_t".to_string(),
+                "u64" | "uint64_t" => "uint64_t".to_string(),
+                _ => name,
+            }
+        }
+        _ => panic!("Type in the signature of {} which can not be spelt in C", function),
+    }
+}
+
+// `PathNotFound` becomes `PATH_NOT_FOUND`
+fn get_define_name(name: &str) -> String {
+    let mut define_name = String::new();
+    for (index, character) in name.chars().enumerate() {
+        if index > 0 && character.is_uppercase() {
+            define_name.push('_');
+        }
+        define_name.extend(character.to_uppercase());
+    }
+    define_name
+}
+
+fn get_error_defines() -> String {
+    let mut defines = vec![ERROR_CODES_COMMENT.to_string(),
+                           format!("#define SAFE_FFI_ERROR_START_RANGE {}", FFI_ERROR_START_RANGE)];
+    for code in errors::get_error_codes().into_iter().filter(|code| code.module == "safe_ffi") {
+        defines.push(format!("#define SAFE_FFI_ERROR_{} (SAFE_FFI_ERROR_START_RANGE - {})",
+                             get_define_name(&code.name),
+                             FFI_ERROR_START_RANGE - code.code));
+    }
+    defines.join("\n")
+}
+
+fn get_network_event_defines() -> String {
+    let events = vec![("Connected", NetworkEvent::Connected),
+                      ("Disconnected", NetworkEvent::Disconnected),
+                      ("Terminated", NetworkEvent::Terminated)];
+
+    let mut defines = vec!["/* Codes passed to the callbacks registered via \
+                            register_network_event_observer */"
+                               .to_string()];
+    for (name, event) in events {
+        let code: i32 = event.into();
+        defines.push(format!("#define SAFE_FFI_NETWORK_EVENT_{} {}", get_define_name(name), code));
+    }
+    defines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+    use std::fs::File;
+    use std::io::{Read, Write};
+    use std::path::Path;
+
+    #[test]
+    fn c_header_is_generated() {
+        let header = generate_header();
+        let header_path = Path::new(env!("CARGO_MANIFEST_DIR")).join(HEADER_PATH);
+
+        if env::var_os("SAFE_FFI_WRITE_HEADER").is_some() {
+            let mut file = unwrap_result!(File::create(&header_path));
+            unwrap_result!(file.write_all(header.as_bytes()));
+            return;
+        }
+
+        let mut checked_in = String::new();
+        let _ = unwrap_result!(unwrap_result!(File::open(&header_path))
+                                   .read_to_string(&mut checked_in));
+        assert!(checked_in == header,
+                "{} is out of date; run this test with SAFE_FFI_WRITE_HEADER set to regenerate it",
+                HEADER_PATH);
+    }
+}
@@ -19,6 +19,10 @@
 pub const SAFE_DRIVE_DIR_NAME: &'static str = "SAFEDrive";
 pub const LAUNCHER_GLOBAL_DIRECTORY_NAME: &'static str = "LauncherReservedDirectory";
 pub const LAUNCHER_GLOBAL_CONFIG_FILE_NAME: &'static str = "LauncherSpecificConfigurationFile";
+pub const LAUNCHER_MESSAGING_KEYS_FILE_NAME: &'static str = "LauncherDnsMessagingKeysFile";
+pub const ASYNC_WORKER_THREAD_COUNT: usize = 4;
+pub const DEFAULT_SERVICE_NAME: &'static str = "www";
+pub const DEFAULT_DOCUMENT_NAME: &'static str = "index.html";
 
 use rustc_serialize::base64::{CharacterSet, Config, Newline};
 
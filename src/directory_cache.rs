@@ -0,0 +1,215 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use std::time::{Duration, Instant};
+
+use safe_core::nfs::directory_listing::DirectoryListing;
+use safe_core::nfs::metadata::directory_key::DirectoryKey;
+
+/// Cache of the directory listings fetched while walking paths, shared by all the requests made
+/// through one client handle. It holds at most `capacity` listings, evicting the least recently
+/// used one when full, and a listing is fetched again once older than `time_to_live`. Changes
+/// made through this crate invalidate the listings they affect, but changes made by other clients
+/// are only seen once the cached listings expire. The cache is disabled while its capacity is 0.
+pub struct DirectoryCache {
+    capacity: usize,
+    time_to_live: Duration,
+    // Ordered from the least to the most recently used
+    entries: Vec<CacheEntry>,
+    hits: u64,
+    misses: u64,
+}
+
+struct CacheEntry {
+    key: DirectoryKey,
+    listing: DirectoryListing,
+    fetched_at: Instant,
+}
+
+/// Configuration and usage statistics of a `DirectoryCache`
+#[derive(RustcEncodable, Debug)]
+pub struct CacheStats {
+    /// Maximum number of listings held, 0 if the cache is disabled
+    pub capacity: usize,
+    /// Age in seconds beyond which a listing is fetched again
+    pub time_to_live_secs: u64,
+    /// Number of listings held
+    pub size: usize,
+    /// Number of lookups answered from the cache
+    pub hits: u64,
+    /// Number of lookups which had to fetch the listing from the network
+    pub misses: u64,
+}
+
+impl DirectoryCache {
+    /// Creates an empty cache, disabled if `capacity` is 0
+    pub fn new(capacity: usize, time_to_live: Duration) -> DirectoryCache {
+        DirectoryCache {
+            capacity: capacity,
+            time_to_live: time_to_live,
+            entries: Vec::with_capacity(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Changes the limits of the cache, evicting the listings which no longer fit. A capacity of 0
+    /// disables the cache.
+    pub fn configure(&mut self, capacity: usize, time_to_live: Duration) {
+        self.capacity = capacity;
+        self.time_to_live = time_to_live;
+        if self.entries.len() > capacity {
+            let excess = self.entries.len() - capacity;
+            let _ = self.entries.drain(..excess);
+        }
+    }
+
+    /// Whether listings are cached at all
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Returns the cached listing of the directory, unless absent or expired
+    pub fn get(&mut self, key: &DirectoryKey) -> Option<DirectoryListing> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let listing = match self.entries.iter().position(|entry| entry.key == *key) {
+            Some(index) => {
+                let entry = self.entries.remove(index);
+                if entry.fetched_at.elapsed() < self.time_to_live {
+                    let listing = entry.listing.clone();
+                    self.entries.push(entry);
+                    Some(listing)
+                } else {
+                    None
+                }
+            }
+            None => None,
+        };
+
+        if listing.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        listing
+    }
+
+    /// Caches `listing`, evicting the least recently used listing if the cache is full
+    pub fn insert(&mut self, listing: DirectoryListing) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let key = listing.get_key().clone();
+        self.entries.retain(|entry| entry.key != key);
+        if self.entries.len() >= self.capacity {
+            let _ = self.entries.remove(0);
+        }
+        self.entries.push(CacheEntry {
+            key: key,
+            listing: listing,
+            fetched_at: Instant::now(),
+        });
+    }
+
+    /// Drops the listing of `directory` along with that of its parent, which holds the metadata of
+    /// `directory` and is updated along with it
+    pub fn invalidate(&mut self, directory: &DirectoryListing) {
+        let key = directory.get_key();
+        let parent_key = directory.get_metadata().get_parent_dir_key();
+        self.entries.retain(|entry| entry.key != *key && Some(&entry.key) != parent_key);
+    }
+
+    /// Returns the limits of the cache along with its usage since it was created
+    pub fn get_stats(&self) -> CacheStats {
+        CacheStats {
+            capacity: self.capacity,
+            time_to_live_secs: self.time_to_live.as_secs(),
+            size: self.entries.len(),
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+}
+
+impl Default for DirectoryCache {
+    fn default() -> DirectoryCache {
+        DirectoryCache::new(0, Duration::from_secs(0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+    use safe_core::nfs::AccessLevel;
+    use safe_core::nfs::directory_listing::DirectoryListing;
+
+    fn get_listing(name: &str, parent: Option<&DirectoryListing>) -> DirectoryListing {
+        unwrap_result!(DirectoryListing::new(name.to_string(),
+                                             ::safe_core::nfs::UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                             Vec::new(),
+                                             false,
+                                             AccessLevel::Private,
+                                             parent.map(|parent| parent.get_key().clone())))
+    }
+
+    #[test]
+    fn eviction_and_invalidation() {
+        let mut cache = DirectoryCache::default();
+        let root = get_listing("root", None);
+        cache.insert(root.clone());
+        assert!(cache.get(root.get_key()).is_none());
+        assert_eq!(cache.get_stats().size, 0);
+
+        cache.configure(2, Duration::from_secs(60));
+        let first = get_listing("first", Some(&root));
+        let second = get_listing("second", Some(&root));
+        cache.insert(root.clone());
+        cache.insert(first.clone());
+        // Makes `first` the least recently used one
+        assert!(cache.get(root.get_key()).is_some());
+        cache.insert(second.clone());
+        assert!(cache.get(first.get_key()).is_none());
+        assert!(cache.get(second.get_key()).is_some());
+
+        cache.invalidate(&second);
+        assert!(cache.get(root.get_key()).is_none());
+        assert!(cache.get(second.get_key()).is_none());
+
+        let stats = cache.get_stats();
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 3);
+        assert_eq!(stats.size, 0);
+    }
+
+    #[test]
+    fn expiry() {
+        let mut cache = DirectoryCache::new(4, Duration::from_millis(100));
+        let root = get_listing("root", None);
+        cache.insert(root.clone());
+        assert!(cache.get(root.get_key()).is_some());
+
+        thread::sleep(Duration::from_millis(200));
+        assert!(cache.get(root.get_key()).is_none());
+        assert_eq!(cache.get_stats().size, 0);
+    }
+}
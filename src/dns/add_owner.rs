@@ -0,0 +1,90 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+use dns::owners;
+use rustc_serialize::json;
+use {ParameterPacket, ResponseType, Action};
+
+#[derive(RustcDecodable, Debug)]
+pub struct AddOwner {
+    pub long_name: String,
+    pub public_key: String,
+}
+
+impl Action for AddOwner {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let new_owner = try!(owners::decode_owner_key(&self.public_key)
+                                 .map_err(|error| error.with_field("public_key".to_string())));
+        let response = try!(owners::change_owners(&params, &self.long_name, |current_owners| {
+            let mut new_owners = current_owners.to_vec();
+            if !new_owners.contains(&new_owner) {
+                new_owners.push(new_owner);
+            }
+            Ok(new_owners)
+        }));
+
+        Ok(Some(try!(json::encode(&response))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Action;
+    use dns::owners;
+    use dns::register_public_id::RegisterPublicId;
+    use test_utils::get_parameter_packet;
+    use safe_core::core::utility;
+    use rustc_serialize::json::Json;
+    use rustc_serialize::base64::ToBase64;
+
+    #[test]
+    fn add_owner() {
+        let parameter_packet = unwrap_result!(get_parameter_packet(false));
+        let other_packet = unwrap_result!(get_parameter_packet(false));
+        let own_key = unwrap_result!(unwrap_result!(parameter_packet.client.lock())
+                                         .get_public_signing_key())
+                          .clone();
+        let other_key = unwrap_result!(unwrap_result!(other_packet.client.lock())
+                                           .get_public_signing_key())
+                            .clone();
+
+        let long_name = unwrap_result!(utility::generate_random_string(10));
+        let mut register_request = RegisterPublicId {
+            long_name: long_name.clone(),
+            extra_owners: None,
+        };
+        assert!(register_request.execute(parameter_packet.clone()).is_ok());
+
+        let mut request = AddOwner {
+            long_name: long_name.clone(),
+            public_key: "Not base64".to_string(),
+        };
+        assert!(request.execute(parameter_packet.clone()).is_err());
+
+        request.public_key = other_key.0.to_base64(::config::get_base64_config());
+        let response = unwrap_option!(unwrap_result!(request.execute(parameter_packet.clone())),
+                                      "");
+        let response = unwrap_result!(Json::from_str(&response));
+        assert_eq!(response.find("posted").and_then(Json::as_boolean), Some(true));
+        assert_eq!(unwrap_result!(owners::get_record_owners(&parameter_packet, &long_name)),
+                   vec![own_key, other_key]);
+
+        // Only owners may add owners
+        let third_packet = unwrap_result!(get_parameter_packet(false));
+        assert!(request.execute(third_packet).is_err());
+    }
+}
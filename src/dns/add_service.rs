@@ -15,7 +15,6 @@
 // Please review the Licences for the specific language governing permissions and limitations
 // relating to use of the SAFE Network Software.
 
-use errors::FfiError;
 use {helper, ParameterPacket, ResponseType, Action};
 use safe_core::dns::dns_operations::DnsOperations;
 
@@ -29,23 +28,9 @@ pub struct AddService {
 
 impl Action for AddService {
     fn execute(&mut self, params: ParameterPacket) -> ResponseType {
-        if self.is_path_shared && !params.safe_drive_access {
-            return Err(FfiError::PermissionDenied);
-        }
-
-        let tokens = helper::tokenise_path(&self.service_home_dir_path, false);
-
-        let start_dir_key = if self.is_path_shared {
-            try!(params.safe_drive_dir_key
-                       .ok_or(FfiError::from("Safe Drive directory key is not present")))
-        } else {
-            try!(params.app_root_dir_key
-                       .ok_or(FfiError::from("Application directory key is not present")))
-        };
-
-        let dir_to_map = try!(helper::get_final_subdirectory(params.client.clone(),
-                                                             &tokens,
-                                                             Some(&start_dir_key)));
+        let dir_to_map = try!(helper::get_directory(&params,
+                                                    self.is_path_shared,
+                                                    &self.service_home_dir_path));
 
         let signing_key = try!(unwrap_result!(params.client.lock()).get_secret_signing_key())
                               .clone();
@@ -56,6 +41,10 @@ impl Action for AddService {
                                        None));
         Ok(None)
     }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.service_home_dir_path.clone())
+    }
 }
 
 #[cfg(test)]
@@ -89,6 +78,7 @@ mod test {
             service_name: "www".to_string(),
             is_path_shared: false,
             service_home_dir_path: format!("/{}", TEST_DIR_NAME).to_string(),
+            extra_owners: None,
         };
         assert!(register_request.execute(parameter_packet.clone()).is_ok());
 
@@ -0,0 +1,97 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+use rustc_serialize::json;
+use rustc_serialize::base64::{FromBase64, ToBase64};
+use dns::messaging_keys;
+use dns::encrypt::PayloadResponse;
+use {ParameterPacket, ResponseType, Action};
+
+/// Decrypts `payload`, given in base64, with the messaging key of a long name owned by the user
+#[derive(RustcDecodable, Debug)]
+pub struct Decrypt {
+    pub long_name: String,
+    pub payload: String,
+}
+
+impl Action for Decrypt {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let sealed = try!(parse_result!(self.payload.from_base64(),
+                                        "Failed Converting from Base64.")
+                              .map_err(|error| error.with_field("payload".to_string())));
+        let (_, secret_key) = try!(messaging_keys::get_keys(&params, &self.long_name));
+        let response = PayloadResponse {
+            payload: try!(messaging_keys::decrypt(&sealed, &secret_key))
+                         .to_base64(::config::get_base64_config()),
+        };
+
+        Ok(Some(try!(json::encode(&response))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Action;
+    use test_utils;
+    use dns::encrypt::Encrypt;
+    use dns::register_public_id::RegisterPublicId;
+    use rustc_serialize::json;
+    use rustc_serialize::base64::ToBase64;
+    use safe_core::core::utility;
+
+    fn get_payload(response: String) -> String {
+        let json = unwrap_result!(json::Json::from_str(&response));
+        unwrap_option!(json.find("payload").and_then(|payload| payload.as_string()), "")
+            .to_string()
+    }
+
+    #[test]
+    fn encrypt_and_decrypt() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        let public_name = unwrap_result!(utility::generate_random_string(10));
+        let mut request = RegisterPublicId {
+            long_name: public_name.clone(),
+            extra_owners: None,
+        };
+        unwrap_result!(request.execute(parameter_packet.clone()));
+
+        let message = b"Hello, SAFE".to_base64(::config::get_base64_config());
+        let mut request = Encrypt {
+            long_name: public_name.clone(),
+            payload: message.clone(),
+        };
+        let unregistered_packet = unwrap_result!(test_utils::get_unregistered_parameter_packet());
+        let response = unwrap_result!(request.execute(unregistered_packet));
+        let sealed = get_payload(unwrap_option!(response, ""));
+        assert!(sealed != message);
+
+        let mut request = Decrypt {
+            long_name: public_name.clone(),
+            payload: sealed,
+        };
+        let response = unwrap_result!(request.execute(parameter_packet.clone()));
+        let opened = get_payload(unwrap_option!(response, ""));
+        assert_eq!(opened, message);
+
+        // Only the owner holds the secret key
+        let other_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        assert!(request.execute(other_packet).is_err());
+
+        request.payload = "Not base64".to_string();
+        assert!(request.execute(parameter_packet).is_err());
+    }
+}
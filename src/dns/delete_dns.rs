@@ -16,6 +16,7 @@
 // relating to use of the SAFE Network Software.
 
 use safe_core::dns::dns_operations::DnsOperations;
+use launcher_config_handler::ConfigHandler;
 use {ParameterPacket, ResponseType, Action};
 
 #[derive(RustcDecodable, Debug)]
@@ -27,9 +28,12 @@ impl Action for DeleteDns {
     fn execute(&mut self, params: ParameterPacket) -> ResponseType {
         let signing_key = try!(unwrap_result!(params.client.lock()).get_secret_signing_key())
                               .clone();
-        let dns_ops = try!(DnsOperations::new(params.client));
+        let dns_ops = try!(DnsOperations::new(params.client.clone()));
         let _ = try!(dns_ops.delete_dns(&self.long_name, &signing_key));
 
+        let config_handler = ConfigHandler::new(params.client);
+        try!(config_handler.remove_messaging_keys(&self.long_name));
+
         Ok(None)
     }
 }
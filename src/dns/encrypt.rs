@@ -0,0 +1,47 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+use rustc_serialize::json;
+use rustc_serialize::base64::{FromBase64, ToBase64};
+use dns::messaging_keys;
+use {ParameterPacket, ResponseType, Action};
+
+/// Encrypts `payload`, given in base64, so only the owner of `long_name` can read it
+#[derive(RustcDecodable, Debug)]
+pub struct Encrypt {
+    pub long_name: String,
+    pub payload: String,
+}
+
+#[derive(RustcEncodable, Debug)]
+pub struct PayloadResponse {
+    pub payload: String,
+}
+
+impl Action for Encrypt {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let plain_text = try!(parse_result!(self.payload.from_base64(),
+                                            "Failed Converting from Base64.")
+                                  .map_err(|error| error.with_field("payload".to_string())));
+        let public_key = try!(messaging_keys::get_public_key(&params, &self.long_name));
+        let response = PayloadResponse {
+            payload: messaging_keys::encrypt(&plain_text, &public_key)
+                         .to_base64(::config::get_base64_config()),
+        };
+
+        Ok(Some(try!(json::encode(&response))))
+    }
+}
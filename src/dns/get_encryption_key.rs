@@ -0,0 +1,86 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+use rustc_serialize::json;
+use rustc_serialize::base64::ToBase64;
+use dns::messaging_keys;
+use {ParameterPacket, ResponseType, Action};
+
+#[derive(RustcDecodable, Debug)]
+pub struct GetEncryptionKey {
+    pub long_name: String,
+}
+
+/// Public messaging key of the long name, in base64
+#[derive(RustcEncodable, Debug)]
+struct GetEncryptionKeyResponse {
+    public_key: String,
+}
+
+impl Action for GetEncryptionKey {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let public_key = try!(messaging_keys::get_public_key(&params, &self.long_name));
+        let response = GetEncryptionKeyResponse {
+            public_key: public_key.0.to_base64(::config::get_base64_config()),
+        };
+
+        Ok(Some(try!(json::encode(&response))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Action;
+    use test_utils;
+    use dns::messaging_keys;
+    use dns::register_public_id::RegisterPublicId;
+    use rustc_serialize::json;
+    use rustc_serialize::base64::FromBase64;
+    use safe_core::core::utility;
+
+    #[test]
+    fn get_encryption_key() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        let public_name = unwrap_result!(utility::generate_random_string(10));
+        let mut request = RegisterPublicId {
+            long_name: public_name.clone(),
+            extra_owners: None,
+        };
+        unwrap_result!(request.execute(parameter_packet.clone()));
+        let (public_key, _) = unwrap_result!(messaging_keys::get_keys(&parameter_packet,
+                                                                      &public_name));
+
+        let mut request = GetEncryptionKey { long_name: public_name.clone() };
+        let response = unwrap_option!(unwrap_result!(request.execute(parameter_packet)), "");
+        let json = unwrap_result!(json::Json::from_str(&response));
+        let encoded = unwrap_option!(json.find("public_key").and_then(|key| key.as_string()), "");
+        assert_eq!(unwrap_result!(encoded.from_base64()), public_key.0.to_vec());
+
+        // The published key must be reachable without an account
+        let unregistered_packet = unwrap_result!(test_utils::get_unregistered_parameter_packet());
+        let response = unwrap_option!(unwrap_result!(request.execute(unregistered_packet)), "");
+        let json = unwrap_result!(json::Json::from_str(&response));
+        let encoded = unwrap_option!(json.find("public_key").and_then(|key| key.as_string()), "");
+        assert_eq!(unwrap_result!(encoded.from_base64()), public_key.0.to_vec());
+
+        let mut request = GetEncryptionKey {
+            long_name: unwrap_result!(utility::generate_random_string(10)),
+        };
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        assert!(request.execute(parameter_packet).is_err());
+    }
+}
@@ -19,7 +19,7 @@ use errors::FfiError;
 use rustc_serialize::json;
 use nfs::file_response::get_response;
 use safe_core::dns::dns_operations::DnsOperations;
-use {helper, ParameterPacket, ResponseType, Action};
+use {helper, path, ParameterPacket, ResponseType, Action};
 use safe_core::nfs::helper::directory_helper::DirectoryHelper;
 
 #[derive(RustcDecodable, Debug)]
@@ -41,10 +41,9 @@ impl Action for GetFile {
         let directory_key = try!(dns_operations.get_service_home_directory_key(&self.long_name,
                                                                                &self.service_name,
                                                                                None));
-        let mut tokens = helper::tokenise_path(&self.file_path, false);
-        let file_name = try!(tokens.pop().ok_or(FfiError::InvalidPath));
+        let (tokens, file_name) = try!(path::split_last(&self.file_path));
         let file_dir = if tokens.len() > 0 {
-            try!(helper::get_final_subdirectory(params.client.clone(),
+            try!(helper::get_final_subdirectory(&params,
                                                 &tokens,
                                                 Some(&directory_key)))
         } else {
@@ -61,4 +60,8 @@ impl Action for GetFile {
 
         Ok(Some(try!(json::encode(&response))))
     }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.file_path.clone())
+    }
 }
@@ -75,6 +75,7 @@ mod test {
             service_name: "www".to_string(),
             is_path_shared: false,
             service_home_dir_path: format!("/{}", TEST_DIR_NAME).to_string(),
+            extra_owners: None,
         };
         assert!(register_request.execute(parameter_packet.clone()).is_ok());
 
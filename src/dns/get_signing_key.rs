@@ -0,0 +1,39 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+use rustc_serialize::json;
+use rustc_serialize::base64::ToBase64;
+use {ParameterPacket, ResponseType, Action};
+
+pub struct GetSigningKey;
+
+/// The user's public signing key, in base64, for others to name as an owner of their long names
+#[derive(RustcEncodable, Debug)]
+struct GetSigningKeyResponse {
+    public_key: String,
+}
+
+impl Action for GetSigningKey {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let public_key = try!(unwrap_result!(params.client.lock()).get_public_signing_key())
+                             .clone();
+        let response = GetSigningKeyResponse {
+            public_key: public_key.0.to_base64(::config::get_base64_config()),
+        };
+
+        Ok(Some(try!(json::encode(&response))))
+    }
+}
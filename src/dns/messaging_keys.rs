@@ -0,0 +1,187 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+use dns::record;
+use errors::FfiError;
+use sodiumoxide::crypto::{box_, sign};
+use safe_core::dns::dns_operations::DnsOperations;
+use safe_core::nfs::metadata::directory_key::DirectoryKey;
+use launcher_config_handler::{ConfigHandler, DnsMessagingKeys};
+
+/// Registers `long_name`, owned by `owners`, with the given services and a freshly generated
+/// messaging keypair, whose public key the DNS record holds.
+///
+/// The keypair is stored in the launcher-reserved configuration so the user can decrypt what is
+/// sent to the long name. It stays there when the long name is handed over to other owners.
+pub fn register(params: &::ParameterPacket,
+                long_name: &String,
+                services: Vec<(String, DirectoryKey)>,
+                owners: Vec<sign::PublicKey>)
+                -> Result<(), FfiError> {
+    let (public_key, secret_key) = box_::gen_keypair();
+    let secret_signing_key = try!(unwrap_result!(params.client.lock()).get_secret_signing_key())
+                                 .clone();
+
+    // The keypair is stored before the record so it can never be lost to a failure in between.
+    // Should registering fail, whatever was stored for the long name before is put back.
+    let config_handler = ConfigHandler::new(params.client.clone());
+    let previous_keys = try!(config_handler.get_messaging_keys(long_name));
+    try!(config_handler.upsert_messaging_keys(DnsMessagingKeys {
+        long_name: long_name.clone(),
+        public_key: public_key.clone(),
+        secret_key: secret_key.clone(),
+    }));
+
+    let registered = DnsOperations::new(params.client.clone())
+                         .map_err(FfiError::from)
+                         .and_then(|dns_operation| {
+                             dns_operation.register_dns(long_name.clone(),
+                                                        &public_key,
+                                                        &secret_key,
+                                                        &services,
+                                                        owners,
+                                                        &secret_signing_key,
+                                                        None)
+                                          .map_err(FfiError::from)
+                         });
+    if let Err(error) = registered {
+        let restored = match previous_keys {
+            Some(keys) => config_handler.upsert_messaging_keys(keys),
+            None => config_handler.remove_messaging_keys(long_name),
+        };
+        return Err(match restored {
+            Ok(()) => error,
+            Err(restore_error) => {
+                FfiError::rollback_failed(format!("messaging keys stored for {:?} ({:?})",
+                                                  long_name,
+                                                  restore_error),
+                                          error)
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Fetches the messaging keypair of a long name owned by the user. Long names registered before
+/// the keypair was stored are looked up in their DNS record instead.
+pub fn get_keys(params: &::ParameterPacket,
+                long_name: &String)
+                -> Result<(box_::PublicKey, box_::SecretKey), FfiError> {
+    let config_handler = ConfigHandler::new(params.client.clone());
+    if let Some(keys) = try!(config_handler.get_messaging_keys(long_name)) {
+        return Ok((keys.public_key, keys.secret_key));
+    }
+
+    let dns_operation = try!(DnsOperations::new(params.client.clone()));
+    Ok(try!(dns_operation.get_messaging_encryption_keys(long_name, None)))
+}
+
+/// Reads the public messaging key of any long name, owned by the user or not, from its DNS record.
+pub fn get_public_key(params: &::ParameterPacket,
+                      long_name: &String)
+                      -> Result<box_::PublicKey, FfiError> {
+    let current_record = try!(record::get(params, long_name));
+    Ok(try!(record::decode(params, &current_record)).encryption_key)
+}
+
+/// Encrypts `plain_text` for the holder of `public_key`. The result carries the public half of a
+/// throwaway keypair and the nonce ahead of the cipher text, so nothing but the recipient's secret
+/// key is needed to decrypt it.
+pub fn encrypt(plain_text: &[u8], public_key: &box_::PublicKey) -> Vec<u8> {
+    let (ephemeral_public_key, ephemeral_secret_key) = box_::gen_keypair();
+    let nonce = box_::gen_nonce();
+    let cipher_text = box_::seal(plain_text, &nonce, public_key, &ephemeral_secret_key);
+
+    let mut sealed = Vec::with_capacity(box_::PUBLICKEYBYTES + box_::NONCEBYTES +
+                                        cipher_text.len());
+    sealed.extend_from_slice(&ephemeral_public_key.0);
+    sealed.extend_from_slice(&nonce.0);
+    sealed.extend_from_slice(&cipher_text);
+    sealed
+}
+
+/// Reverses `encrypt`.
+pub fn decrypt(sealed: &[u8], secret_key: &box_::SecretKey) -> Result<Vec<u8>, FfiError> {
+    let header_size = box_::PUBLICKEYBYTES + box_::NONCEBYTES;
+    if sealed.len() < header_size {
+        return Err(FfiError::SpecificParseError("Encrypted payload is too short".to_string()));
+    }
+    let ephemeral_public_key =
+        unwrap_option!(box_::PublicKey::from_slice(&sealed[..box_::PUBLICKEYBYTES]),
+                       "Logic Error - Slice length was checked - Report bug.");
+    let nonce = unwrap_option!(box_::Nonce::from_slice(&sealed[box_::PUBLICKEYBYTES..header_size]),
+                               "Logic Error - Slice length was checked - Report bug.");
+    box_::open(&sealed[header_size..],
+               &nonce,
+               &ephemeral_public_key,
+               secret_key)
+        .map_err(|()| {
+            FfiError::SpecificParseError("Payload could not be decrypted with the messaging key"
+                                             .to_string())
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decrypt, encrypt, get_public_key, register};
+    use test_utils;
+    use sodiumoxide::crypto::box_;
+    use safe_core::core::utility;
+    use launcher_config_handler::ConfigHandler;
+
+    #[test]
+    fn register_messaging_keys() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        let long_name = unwrap_result!(utility::generate_random_string(10));
+        let own_key = unwrap_result!(unwrap_result!(parameter_packet.client.lock())
+                                         .get_public_signing_key())
+                          .clone();
+        unwrap_result!(register(&parameter_packet, &long_name, Vec::new(), vec![own_key]));
+
+        let config_handler = ConfigHandler::new(parameter_packet.client.clone());
+        let keys = unwrap_option!(unwrap_result!(config_handler.get_messaging_keys(&long_name)),
+                                  "Messaging keys not stored");
+        assert_eq!(unwrap_result!(get_public_key(&parameter_packet, &long_name)),
+                   keys.public_key);
+
+        // Registering the long name again fails, leaving the keys of the first registration
+        assert!(register(&parameter_packet, &long_name, Vec::new(), vec![own_key]).is_err());
+        let kept_keys = unwrap_result!(config_handler.get_messaging_keys(&long_name));
+        let kept_keys = unwrap_option!(kept_keys, "Messaging keys not kept");
+        assert_eq!(kept_keys.public_key, keys.public_key);
+    }
+
+    #[test]
+    fn encrypt_and_decrypt() {
+        let (public_key, secret_key) = box_::gen_keypair();
+        let plain_text = b"Hello, SAFE".to_vec();
+
+        let sealed = encrypt(&plain_text, &public_key);
+        assert!(sealed.len() > plain_text.len() + box_::PUBLICKEYBYTES + box_::NONCEBYTES);
+        assert_eq!(unwrap_result!(decrypt(&sealed, &secret_key)), plain_text);
+
+        let (_, other_secret_key) = box_::gen_keypair();
+        assert!(decrypt(&sealed, &other_secret_key).is_err());
+
+        let mut tampered = sealed.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(decrypt(&tampered, &secret_key).is_err());
+
+        assert!(decrypt(&sealed[..box_::NONCEBYTES], &secret_key).is_err());
+    }
+}
@@ -14,91 +14,155 @@
 //
 // Please review the Licences for the specific language governing permissions and limitations
 // relating to use of the SAFE Network Software.
-use std::fmt;
-
-use rustc_serialize::Decoder;
-use rustc_serialize::Decodable;
+use helper::{self, ActionDecoder, describe_action};
 use errors::FfiError;
+use rustc_serialize::json::Json;
+use meta::schema::{ActionSchema, FieldSchema, optional, required};
+use meta::schema::FieldType::{Array, Bool, Integer};
+use meta::schema::FieldType::String as Text;
 
+mod decrypt;
+mod encrypt;
 mod get_file;
+pub mod resolve;
+mod owners;
+mod record;
 mod delete_dns;
 mod add_service;
 mod register_dns;
 mod get_services;
 mod get_long_names;
 mod delete_service;
+mod update_service;
+mod messaging_keys;
 mod register_public_id;
 mod get_service_directory;
+mod get_signing_key;
+mod get_encryption_key;
+mod add_owner;
+mod remove_owner;
+mod transfer_ownership;
+mod sign_owner_change;
 
-pub fn action_dispatcher<D>(action: String,
-                            params: ::ParameterPacket,
-                            decoder: &mut D)
-                            -> ::ResponseType
-    where D: Decoder,
-          D::Error: fmt::Debug
-{
-    let mut action = try!(get_action(action, decoder));
-    action.execute(params)
+pub fn action_dispatcher(action: String,
+                         params: ::ParameterPacket,
+                         request: &Json)
+                         -> ::ResponseType {
+    let mut action = try!(get_action(action, request));
+    action.execute(params).map_err(|error| {
+        match action.get_path() {
+            Some(path) => error.with_path(path),
+            None => error,
+        }
+    })
 }
 
-fn get_action<D>(action: String, decoder: &mut D) -> Result<Box<::Action>, FfiError>
-    where D: Decoder,
-          D::Error: fmt::Debug
-{
-    Ok(match &action[..] {
-        "register-public-id" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            register_public_id::RegisterPublicId::decode(d)
-                                        }),
-                                        "")))
-        }
-        "register-dns" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            register_dns::RegisterDns::decode(d)
-                                        }),
-                                        "")))
-        }
-        "add-service" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            add_service::AddService::decode(d)
-                                        }),
-                                        "")))
-        }
-        "get-home-dir" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            get_service_directory::GetServiceDirectory::decode(d)
-                                        }),
-                                        "")))
-        }
-        "get-file" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            get_file::GetFile::decode(d)
-                                        }),
-                                        "")))
-        }
-        "get-long-names" => Box::new(get_long_names::GetLongNames),
-        "get-services" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            get_services::GetServices::decode(d)
-                                        }),
-                                        "")))
-        }
-        "delete-dns" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            delete_dns::DeleteDns::decode(d)
-                                        }),
-                                        "")))
-        }
-        "delete-service" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            delete_service::DeleteService::decode(d)
-                                        }),
-                                        "")))
-        }
-        _ => {
-            return Err(FfiError::SpecificParseError(format!("Unsupported action {:?} for this \
-                                                             endpoint.",
-                                                            action)))
+/// Describes the actions of this module
+pub fn get_action_schemas() -> Vec<ActionSchema> {
+    get_actions().into_iter().map(|(schema, _)| schema).collect()
+}
+
+// Table of the actions of this module, which both their schemas and the decoding of requests are
+// taken from
+fn get_actions() -> Vec<(ActionSchema, ActionDecoder)> {
+    fn service_fields() -> Vec<FieldSchema> {
+        vec![required("long_name", Text), required("service_name", Text)]
+    }
+    fn payload_fields() -> Vec<FieldSchema> {
+        vec![required("long_name", Text), required("payload", Text)]
+    }
+    fn service_home_dir_fields() -> Vec<FieldSchema> {
+        vec![required("long_name", Text),
+             required("service_name", Text),
+             required("is_path_shared", Bool),
+             required("service_home_dir_path", Text)]
+    }
+    fn owner_fields() -> Vec<FieldSchema> {
+        vec![required("long_name", Text), required("public_key", Text)]
+    }
+    fn extra_owners_field() -> FieldSchema {
+        optional("extra_owners", Array(vec![required("public_key", Text)]))
+    }
+    // Decoders of the actions without data
+    fn decode_get_long_names(_: &Json, _: &[FieldSchema]) -> Result<Box<::Action>, FfiError> {
+        Ok(Box::new(get_long_names::GetLongNames))
+    }
+    fn decode_get_signing_key(_: &Json, _: &[FieldSchema]) -> Result<Box<::Action>, FfiError> {
+        Ok(Box::new(get_signing_key::GetSigningKey))
+    }
+
+    let mut register_dns_fields = service_home_dir_fields();
+    register_dns_fields.push(extra_owners_field());
+    let get_file_fields = vec![required("long_name", Text),
+                               required("service_name", Text),
+                               required("offset", Integer),
+                               required("length", Integer),
+                               required("file_path", Text),
+                               required("include_metadata", Bool)];
+    let resolve_fields = vec![required("url", Text),
+                              optional("offset", Integer),
+                              optional("length", Integer),
+                              optional("include_metadata", Bool)];
+
+    vec![describe_action::<register_public_id::RegisterPublicId>("register-public-id",
+                                                                 vec![required("long_name",
+                                                                               Text),
+                                                                      extra_owners_field()]),
+         describe_action::<register_dns::RegisterDns>("register-dns", register_dns_fields),
+         describe_action::<add_service::AddService>("add-service", service_home_dir_fields()),
+         describe_action::<get_service_directory::GetServiceDirectory>("get-home-dir",
+                                                                       service_fields()),
+         describe_action::<get_file::GetFile>("get-file", get_file_fields),
+         (ActionSchema::new("get-long-names", Vec::new()),
+          decode_get_long_names as ActionDecoder),
+         (ActionSchema::new("get-signing-key", Vec::new()),
+          decode_get_signing_key as ActionDecoder),
+         describe_action::<get_services::GetServices>("get-services",
+                                                      vec![required("long_name", Text)]),
+         describe_action::<add_owner::AddOwner>("add-owner", owner_fields()),
+         describe_action::<remove_owner::RemoveOwner>("remove-owner", owner_fields()),
+         describe_action::<transfer_ownership::TransferOwnership>("transfer-ownership",
+                                                                  owner_fields()),
+         describe_action::<sign_owner_change::SignOwnerChange>("sign-owner-change",
+                                                               vec![required("long_name", Text),
+                                                                    required("record", Text)]),
+         describe_action::<delete_dns::DeleteDns>("delete-dns", vec![required("long_name", Text)]),
+         describe_action::<delete_service::DeleteService>("delete-service", service_fields()),
+         describe_action::<update_service::UpdateService>("update-service",
+                                                          service_home_dir_fields()),
+         describe_action::<resolve::Resolve>("resolve", resolve_fields),
+         describe_action::<get_encryption_key::GetEncryptionKey>("get-encryption-key",
+                                                                 vec![required("long_name",
+                                                                               Text)]),
+         describe_action::<encrypt::Encrypt>("encrypt", payload_fields()),
+         describe_action::<decrypt::Decrypt>("decrypt", payload_fields())]
+}
+
+fn get_action(action: String, request: &Json) -> Result<Box<::Action>, FfiError> {
+    helper::find_action(get_actions(), action, request)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::get_action;
+    use meta::schema;
+
+    #[test]
+    fn action_schemas_match_decoding() {
+        for action in get_action_schemas() {
+            let request = schema::get_sample_request(&action.fields, true, None);
+            assert!(get_action(action.name.to_string(), &request).is_ok(),
+                    "{} fails to decode its described fields",
+                    action.name);
+
+            for field in &action.fields {
+                let request = schema::get_sample_request(&action.fields, true, Some(field.name));
+                assert!(get_action(action.name.to_string(), &request).is_err(),
+                        "{} decodes without its required field {}",
+                        action.name,
+                        field.name);
+            }
         }
-    })
+    }
 }
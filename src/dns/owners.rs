@@ -0,0 +1,217 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+use ParameterPacket;
+use dns::record;
+use errors::FfiError;
+use sodiumoxide::crypto::sign;
+use rustc_serialize::base64::{FromBase64, ToBase64};
+use maidsafe_utilities::serialisation::serialise;
+use routing::{RoutingError, StructuredData};
+
+/// Public signing key, in base64, of an owner of a long name
+#[derive(RustcDecodable, Debug)]
+pub struct OwnerKey {
+    pub public_key: String,
+}
+
+/// Owners to register a long name with: the user's own key followed by the decoded
+/// `extra_owners`, each listed once.
+pub fn get_owners(own_key: sign::PublicKey,
+                  extra_owners: &Option<Vec<OwnerKey>>)
+                  -> Result<Vec<sign::PublicKey>, FfiError> {
+    let mut owners = vec![own_key];
+    if let Some(ref extra_owners) = *extra_owners {
+        for owner in extra_owners {
+            let key = try!(decode_owner_key(&owner.public_key));
+            if !owners.contains(&key) {
+                owners.push(key);
+            }
+        }
+    }
+
+    Ok(owners)
+}
+
+/// Decodes a public signing key given in base64
+pub fn decode_owner_key(public_key: &str) -> Result<sign::PublicKey, FfiError> {
+    let bytes = try!(parse_result!(public_key.from_base64(), "Failed Converting from Base64."));
+    sign::PublicKey::from_slice(&bytes).ok_or_else(|| {
+        FfiError::SpecificParseError(format!("Invalid public signing key {:?}", public_key))
+    })
+}
+
+/// Current owners of the DNS record of `long_name`
+#[cfg(test)]
+pub fn get_record_owners(params: &ParameterPacket,
+                         long_name: &str)
+                         -> Result<Vec<sign::PublicKey>, FfiError> {
+    Ok(try!(record::get(params, long_name)).get_owner_keys().clone())
+}
+
+/// Next version of a DNS record changing its owners, as far as it has been signed
+#[derive(RustcEncodable, Debug)]
+pub struct OwnerChangeResponse {
+    /// Whether enough owners signed the change for it to be posted
+    pub posted: bool,
+    /// The record serialised in base64, for the other owners to sign until it is posted
+    pub record: String,
+}
+
+/// Prepares the next version of the DNS record of `long_name`, with its data unchanged and the
+/// owners `get_new_owners` returns for the current ones, which are named as the previous ones. The
+/// user signs it and it is posted if that is enough, see `sign_owner_change`.
+pub fn change_owners<F>(params: &ParameterPacket,
+                        long_name: &str,
+                        get_new_owners: F)
+                        -> Result<OwnerChangeResponse, FfiError>
+    where F: FnOnce(&[sign::PublicKey]) -> Result<Vec<sign::PublicKey>, FfiError>
+{
+    let current_record = try!(record::get(params, long_name));
+    let owners = current_record.get_owner_keys().clone();
+    let new_owners = try!(get_new_owners(&owners));
+    if new_owners.is_empty() {
+        return Err(FfiError::SpecificParseError("A long name must keep at least one owner"
+                                                    .to_string()));
+    }
+
+    let updated_record = try!(StructuredData::new(record::DNS_TAG,
+                                                  *current_record.get_identifier(),
+                                                  current_record.get_version() + 1,
+                                                  current_record.get_data().clone(),
+                                                  new_owners,
+                                                  owners,
+                                                  None)
+                                  .map_err(get_routing_error));
+    sign_owner_change(params, updated_record)
+}
+
+/// Adds the user's signature to a record changing the owners of a long name, the user having to
+/// be one of the owners it replaces. The network only accepts the change once more than half of
+/// those owners signed it, so the record is posted as soon as it is signed by that many and is
+/// otherwise handed back to be signed by the others.
+pub fn sign_owner_change(params: &ParameterPacket,
+                         mut updated_record: StructuredData)
+                         -> Result<OwnerChangeResponse, FfiError> {
+    let (own_key, signing_key) = {
+        let client = unwrap_result!(params.client.lock());
+        (try!(client.get_public_signing_key()).clone(),
+         try!(client.get_secret_signing_key()).clone())
+    };
+    if !updated_record.get_previous_owner_keys().contains(&own_key) {
+        return Err(FfiError::PermissionDenied);
+    }
+    let _ = try!(updated_record.add_signature(&signing_key).map_err(get_routing_error));
+
+    let posted = updated_record.get_previous_owner_signatures().len() * 2 >
+                 updated_record.get_previous_owner_keys().len();
+    let encoded_record = try!(serialise(&updated_record)).to_base64(::config::get_base64_config());
+    if posted {
+        try!(record::post(params, updated_record));
+    }
+
+    Ok(OwnerChangeResponse {
+        posted: posted,
+        record: encoded_record,
+    })
+}
+
+fn get_routing_error(error: RoutingError) -> FfiError {
+    FfiError::Unexpected(format!("Failed to sign the DNS record: {:?}", error))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{OwnerKey, change_owners, get_owners, get_record_owners};
+    use errors::FfiError;
+    use dns::messaging_keys;
+    use test_utils::get_parameter_packet;
+    use safe_core::core::utility;
+    use sodiumoxide::crypto::sign;
+    use rustc_serialize::base64::ToBase64;
+
+    fn owner_key(key: &sign::PublicKey) -> OwnerKey {
+        OwnerKey { public_key: key.0.to_base64(::config::get_base64_config()) }
+    }
+
+    #[test]
+    fn owners_from_extra_keys() {
+        let (own_key, _) = sign::gen_keypair();
+        let (other_key, _) = sign::gen_keypair();
+
+        assert_eq!(unwrap_result!(get_owners(own_key, &None)), vec![own_key]);
+        assert_eq!(unwrap_result!(get_owners(own_key, &Some(Vec::new()))), vec![own_key]);
+
+        let extra_owners = Some(vec![owner_key(&other_key),
+                                     owner_key(&own_key),
+                                     owner_key(&other_key)]);
+        assert_eq!(unwrap_result!(get_owners(own_key, &extra_owners)),
+                   vec![own_key, other_key]);
+
+        let not_base64 = Some(vec![OwnerKey { public_key: "Not base64".to_string() }]);
+        assert!(get_owners(own_key, &not_base64).is_err());
+
+        let too_short = Some(vec![OwnerKey {
+                                      public_key: vec![1u8; 8]
+                                                      .to_base64(::config::get_base64_config()),
+                                  }]);
+        assert!(get_owners(own_key, &too_short).is_err());
+    }
+
+    #[test]
+    fn change_record_owners() {
+        let parameter_packet = unwrap_result!(get_parameter_packet(false));
+        let own_key = unwrap_result!(unwrap_result!(parameter_packet.client.lock())
+                                         .get_public_signing_key())
+                          .clone();
+        let (other_key, _) = sign::gen_keypair();
+
+        let long_name = unwrap_result!(utility::generate_random_string(10));
+        unwrap_result!(messaging_keys::register(&parameter_packet,
+                                                &long_name,
+                                                Vec::new(),
+                                                vec![own_key]));
+        assert!(change_owners(&parameter_packet, &long_name, |_| Ok(Vec::new())).is_err());
+
+        // The user's signature is enough while the user is the only owner
+        let response = unwrap_result!(change_owners(&parameter_packet, &long_name, |owners| {
+            let mut owners = owners.to_vec();
+            owners.push(other_key);
+            Ok(owners)
+        }));
+        assert!(response.posted);
+        assert_eq!(unwrap_result!(get_record_owners(&parameter_packet, &long_name)),
+                   vec![own_key, other_key]);
+
+        // but no longer once there are two
+        let response = unwrap_result!(change_owners(&parameter_packet,
+                                                    &long_name,
+                                                    |_| Ok(vec![own_key])));
+        assert!(!response.posted);
+        assert_eq!(unwrap_result!(get_record_owners(&parameter_packet, &long_name)),
+                   vec![own_key, other_key]);
+
+        let response = unwrap_result!(change_owners(&parameter_packet,
+                                                    &long_name,
+                                                    |_| Ok(vec![other_key])));
+        assert!(!response.posted);
+        let other_packet = unwrap_result!(get_parameter_packet(false));
+        match change_owners(&other_packet, &long_name, |_| Ok(vec![other_key])) {
+            Err(FfiError::PermissionDenied) => (),
+            result => panic!("Expected PermissionDenied, got {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+use std::collections::HashMap;
+
+use ParameterPacket;
+use errors::FfiError;
+use xor_name::XorName;
+use sodiumoxide::crypto::{box_, hash};
+use routing::{Data, DataIdentifier, StructuredData};
+use maidsafe_utilities::serialisation::{deserialise, serialise};
+use safe_core::core::errors::CoreError;
+use safe_core::core::structured_data;
+use safe_core::dns::errors::DnsError;
+use safe_core::nfs::metadata::directory_key::DirectoryKey;
+
+/// Type tag of the structured data holding DNS records, as safe_core stores them
+pub const DNS_TAG: u64 = 5;
+
+/// Contents of a DNS record, laid out the way safe_core serialises them
+#[derive(RustcEncodable, RustcDecodable, Debug)]
+pub struct DnsRecord {
+    pub long_name: String,
+    pub services: HashMap<String, DirectoryKey>,
+    pub encryption_key: box_::PublicKey,
+}
+
+/// Identifier of the structured data holding the DNS record of `long_name`
+pub fn get_identifier(long_name: &str) -> XorName {
+    XorName::new(hash::sha512::hash(long_name.as_bytes()).0)
+}
+
+/// Fetches the structured data holding the DNS record of `long_name` from the network
+pub fn get(params: &ParameterPacket, long_name: &str) -> Result<StructuredData, FfiError> {
+    let request = DataIdentifier::Structured(get_identifier(long_name), DNS_TAG);
+    let response_getter = try!(unwrap_result!(params.client.lock()).get(request, None));
+    match response_getter.get() {
+        Ok(Data::Structured(record)) => Ok(record),
+        Ok(_) => Err(FfiError::from(CoreError::ReceivedUnexpectedData)),
+        Err(CoreError::GetFailure { .. }) => Err(FfiError::from(DnsError::DnsRecordNotFound)),
+        Err(error) => Err(FfiError::from(error)),
+    }
+}
+
+/// Reads the contents of a DNS record
+pub fn decode(params: &ParameterPacket, record: &StructuredData) -> Result<DnsRecord, FfiError> {
+    let data = try!(structured_data::get_data(params.client.clone(), record, None));
+    Ok(try!(deserialise(&data)))
+}
+
+/// Posts the next version of `record` with `contents` and the same owners, signed by the user
+pub fn update(params: &ParameterPacket,
+              record: &StructuredData,
+              contents: &DnsRecord)
+              -> Result<(), FfiError> {
+    let signing_key = try!(unwrap_result!(params.client.lock()).get_secret_signing_key()).clone();
+    let updated_record = try!(structured_data::create(params.client.clone(),
+                                                      DNS_TAG,
+                                                      *record.get_identifier(),
+                                                      record.get_version() + 1,
+                                                      try!(serialise(contents)),
+                                                      record.get_owner_keys().clone(),
+                                                      Vec::new(),
+                                                      &signing_key,
+                                                      None));
+    post(params, updated_record)
+}
+
+/// Posts a new version of a DNS record
+pub fn post(params: &ParameterPacket, record: StructuredData) -> Result<(), FfiError> {
+    let response_getter = try!(unwrap_result!(params.client.lock())
+                                   .post(Data::Structured(record), None));
+    Ok(try!(response_getter.get()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dns::messaging_keys;
+    use test_utils::get_parameter_packet;
+    use safe_core::core::utility;
+
+    // Reads back a record registered through safe_core, which fails unless the tag, the
+    // identifier and the layout of the contents match the ones safe_core uses
+    #[test]
+    fn read_registered_record() {
+        let parameter_packet = unwrap_result!(get_parameter_packet(false));
+        let own_key = unwrap_result!(unwrap_result!(parameter_packet.client.lock())
+                                         .get_public_signing_key())
+                          .clone();
+        let long_name = unwrap_result!(utility::generate_random_string(10));
+        unwrap_result!(messaging_keys::register(&parameter_packet,
+                                                &long_name,
+                                                Vec::new(),
+                                                vec![own_key]));
+
+        let record = unwrap_result!(get(&parameter_packet, &long_name));
+        assert_eq!(record.get_type_tag(), DNS_TAG);
+        let contents = unwrap_result!(decode(&parameter_packet, &record));
+        assert_eq!(contents.long_name, long_name);
+
+        let missing_name = unwrap_result!(utility::generate_random_string(10));
+        assert!(get(&parameter_packet, &missing_name).is_err());
+    }
+}
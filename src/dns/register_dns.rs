@@ -15,58 +15,31 @@
 // Please review the Licences for the specific language governing permissions and limitations
 // relating to use of the SAFE Network Software.
 
-use sodiumoxide::crypto::box_;
-
-use errors::FfiError;
-use safe_core::dns::dns_operations::DnsOperations;
-
 #[derive(RustcDecodable, Debug)]
 pub struct RegisterDns {
     pub long_name: String,
     pub service_name: String,
     pub is_path_shared: bool,
     pub service_home_dir_path: String,
+    pub extra_owners: Option<Vec<::dns::owners::OwnerKey>>,
 }
 
 impl ::Action for RegisterDns {
     fn execute(&mut self, params: ::ParameterPacket) -> ::ResponseType {
-        if self.is_path_shared && !params.safe_drive_access {
-            return Err(FfiError::PermissionDenied);
-        }
-
-        let tokens = ::helper::tokenise_path(&self.service_home_dir_path, false);
-
-        let start_dir_key = if self.is_path_shared {
-            try!(params.safe_drive_dir_key
-                       .ok_or(FfiError::from("Safe Drive directory key is not present")))
-        } else {
-            try!(params.app_root_dir_key
-                       .ok_or(FfiError::from("Application directory key is not present")))
-        };
-
-        let dir_to_map = try!(::helper::get_final_subdirectory(params.client.clone(),
-                                                               &tokens,
-                                                               Some(&start_dir_key)));
+        let dir_to_map = try!(::helper::get_directory(&params,
+                                                      self.is_path_shared,
+                                                      &self.service_home_dir_path));
 
-        let (msg_public_key, msg_secret_key) = box_::gen_keypair();
         let services = vec![(self.service_name.clone(), (dir_to_map.get_key().clone()))];
-        let public_signing_key = try!(unwrap_result!(params.client.lock())
-                                          .get_public_signing_key())
-                                     .clone();
-        let secret_signing_key = try!(unwrap_result!(params.client.lock())
-                                          .get_secret_signing_key())
-                                     .clone();
-        let dns_operation = try!(DnsOperations::new(params.client
-                                                          .clone()));
-        try!(dns_operation.register_dns(self.long_name.clone(),
-                                        &msg_public_key,
-                                        &msg_secret_key,
-                                        &services,
-                                        vec![public_signing_key],
-                                        &secret_signing_key,
-                                        None));
+        let own_key = try!(unwrap_result!(params.client.lock()).get_public_signing_key()).clone();
+        let owners = try!(::dns::owners::get_owners(own_key, &self.extra_owners));
+        try!(::dns::messaging_keys::register(&params, &self.long_name, services, owners));
         Ok(None)
     }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.service_home_dir_path.clone())
+    }
 }
 
 #[cfg(test)]
@@ -101,6 +74,7 @@ mod test {
             service_name: "www".to_string(),
             is_path_shared: false,
             service_home_dir_path: "/test_dir2".to_string(),
+            extra_owners: None,
         };
         assert!(request.execute(parameter_packet.clone()).is_err());
         request.service_home_dir_path = format!("/{}", TEST_DIR_NAME);
@@ -15,33 +15,17 @@
 // Please review the Licences for the specific language governing permissions and limitations
 // relating to use of the SAFE Network Software.
 
-use sodiumoxide::crypto::box_;
-use safe_core::dns::dns_operations::DnsOperations;
-
 #[derive(RustcDecodable, Debug)]
 pub struct RegisterPublicId {
     pub long_name: String,
+    pub extra_owners: Option<Vec<::dns::owners::OwnerKey>>,
 }
 
 impl ::Action for RegisterPublicId {
     fn execute(&mut self, params: ::ParameterPacket) -> ::ResponseType {
-        let (msg_public_key, msg_secret_key) = box_::gen_keypair();
-        let services = vec![];
-        let public_signing_key = try!(unwrap_result!(params.client.lock())
-                                          .get_public_signing_key())
-                                     .clone();
-        let secret_signing_key = try!(unwrap_result!(params.client.lock())
-                                          .get_secret_signing_key())
-                                     .clone();
-        let dns_operation = try!(DnsOperations::new(params.client
-                                                          .clone()));
-        try!(dns_operation.register_dns(self.long_name.clone(),
-                                        &msg_public_key,
-                                        &msg_secret_key,
-                                        &services,
-                                        vec![public_signing_key],
-                                        &secret_signing_key,
-                                        None));
+        let own_key = try!(unwrap_result!(params.client.lock()).get_public_signing_key()).clone();
+        let owners = try!(::dns::owners::get_owners(own_key, &self.extra_owners));
+        try!(::dns::messaging_keys::register(&params, &self.long_name, Vec::new(), owners));
         Ok(None)
     }
 }
@@ -50,17 +34,41 @@ impl ::Action for RegisterPublicId {
 mod test {
     use super::*;
     use Action;
+    use dns::owners::OwnerKey;
     use test_utils::get_parameter_packet;
     use safe_core::core::utility;
+    use rustc_serialize::base64::ToBase64;
 
     #[test]
     fn register_public_id() {
         let parameter_packet = unwrap_result!(get_parameter_packet(false));
         let public_name = unwrap_result!(utility::generate_random_string(10));
-        let mut request = RegisterPublicId { long_name: public_name.clone() };
+        let mut request = RegisterPublicId {
+            long_name: public_name.clone(),
+            extra_owners: None,
+        };
         assert!(request.execute(parameter_packet.clone()).is_ok());
         // let parameter_packet = unwrap_result!(get_parameter_packet(false));
-        // let mut request = RegisterPublicId { long_name: public_name };
+        // let mut request = RegisterPublicId { long_name: public_name, extra_owners: None };
         // assert!(request.execute(parameter_packet.clone()).is_err());
     }
+
+    #[test]
+    fn register_public_id_with_extra_owners() {
+        let parameter_packet = unwrap_result!(get_parameter_packet(false));
+        let other_packet = unwrap_result!(get_parameter_packet(false));
+        let other_key = unwrap_result!(unwrap_result!(other_packet.client.lock())
+                                           .get_public_signing_key())
+                            .clone();
+
+        let mut request = RegisterPublicId {
+            long_name: unwrap_result!(utility::generate_random_string(10)),
+            extra_owners: Some(vec![OwnerKey { public_key: "Not base64".to_string() }]),
+        };
+        assert!(request.execute(parameter_packet.clone()).is_err());
+
+        let public_key = other_key.0.to_base64(::config::get_base64_config());
+        request.extra_owners = Some(vec![OwnerKey { public_key: public_key }]);
+        assert!(request.execute(parameter_packet).is_ok());
+    }
 }
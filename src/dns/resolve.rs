@@ -0,0 +1,313 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use errors::FfiError;
+use rustc_serialize::json;
+use nfs::file_response::{self, GetFileResponse};
+use nfs::directory_response::{self, GetDirResponse};
+use safe_core::nfs::file::File;
+use safe_core::dns::dns_operations::DnsOperations;
+use safe_core::nfs::directory_listing::DirectoryListing;
+use {helper, path, ParameterPacket, ResponseType, Action};
+use config::{DEFAULT_DOCUMENT_NAME, DEFAULT_SERVICE_NAME};
+
+const SCHEME: &'static str = "safe://";
+
+#[derive(RustcDecodable, Debug)]
+pub struct Resolve {
+    url: String,
+    offset: Option<i64>,
+    length: Option<i64>,
+    include_metadata: Option<bool>,
+}
+
+/// What a `safe://` URL resolved to. Exactly one of `file` and `directory` is set, according to
+/// `resolved_to`, and `path` is the path of the content within the service's home directory.
+#[derive(RustcEncodable, Debug)]
+struct ResolveResponse {
+    resolved_to: String,
+    long_name: String,
+    service_name: String,
+    path: String,
+    file: Option<GetFileResponse>,
+    directory: Option<GetDirResponse>,
+}
+
+/// Parts of a `safe://[service.]long_name[/path]` URL. The service defaults to `www` and the path
+/// is split into percent-decoded names.
+#[derive(Debug, PartialEq)]
+pub struct SafeUrl {
+    pub long_name: String,
+    pub service_name: String,
+    pub tokens: Vec<String>,
+}
+
+/// Content a `SafeUrl` leads to, along with its path within the service's home directory
+pub enum Resolved {
+    File(File, String),
+    Directory(DirectoryListing, String),
+}
+
+impl SafeUrl {
+    /// Parses `url`, ignoring any query or fragment
+    pub fn parse(url: &str) -> Result<SafeUrl, FfiError> {
+        let invalid = |reason: &str| {
+            FfiError::SpecificParseError(format!("Invalid URL {:?} - {}", url, reason))
+        };
+        // No other character lower-cases into the ASCII of the scheme, so slicing it off is safe
+        if !url.to_lowercase().starts_with(SCHEME) {
+            return Err(invalid("expected it to start with safe://"));
+        }
+
+        let rest = &url[SCHEME.len()..];
+        let rest = &rest[..rest.find(|c| c == '?' || c == '#').unwrap_or(rest.len())];
+        let (host, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, ""),
+        };
+        let (service_name, long_name) = match host.find('.') {
+            Some(index) => (&host[..index], &host[index + 1..]),
+            None => (DEFAULT_SERVICE_NAME, host),
+        };
+        if service_name.is_empty() || long_name.is_empty() {
+            return Err(invalid("expected a host of the form [service.]long_name"));
+        }
+
+        let mut tokens = Vec::new();
+        for token in try!(path::tokenise(path)) {
+            let name = try!(percent_decode(&token).ok_or_else(|| {
+                invalid(&format!("{:?} is not valid percent-encoded UTF-8", token))
+            }));
+            try!(path::validate_name(&name));
+            tokens.push(name);
+        }
+
+        Ok(SafeUrl {
+            long_name: long_name.to_string(),
+            service_name: service_name.to_string(),
+            tokens: tokens,
+        })
+    }
+
+    /// Finds the content the URL leads to. A path naming a directory, or no path at all, leads to
+    /// the directory's `index.html` if it has one, or else to the directory itself.
+    pub fn resolve(&self, params: &ParameterPacket) -> Result<Resolved, FfiError> {
+        let dns_operations = match params.app_root_dir_key {
+            Some(_) => try!(DnsOperations::new(params.client.clone())),
+            None => DnsOperations::new_unregistered(params.client.clone()),
+        };
+        let home_dir_key = try!(dns_operations.get_service_home_directory_key(&self.long_name,
+                                                                              &self.service_name,
+                                                                              None));
+
+        let parent_tokens = self.tokens[..self.tokens.len().saturating_sub(1)].to_vec();
+        let mut dir = try!(helper::get_final_subdirectory(params,
+                                                          &parent_tokens,
+                                                          Some(&home_dir_key)));
+        let mut path = parent_tokens.iter().fold(String::new(), |joined, name| joined + "/" + name);
+        if let Some(name) = self.tokens.last() {
+            path = format!("{}/{}", path, name);
+            if let Some(file) = dir.find_file(name) {
+                return Ok(Resolved::File(file.clone(), path));
+            }
+            let dir_key = dir.get_key().clone();
+            dir = try!(helper::get_final_subdirectory(params, &vec![name.clone()], Some(&dir_key)));
+        }
+
+        let index = DEFAULT_DOCUMENT_NAME.to_string();
+        if let Some(file) = dir.find_file(&index).cloned() {
+            return Ok(Resolved::File(file, format!("{}/{}", path, index)));
+        }
+        if path.is_empty() {
+            path = "/".to_string();
+        }
+        Ok(Resolved::Directory(dir, path))
+    }
+}
+
+impl Action for Resolve {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let url = try!(SafeUrl::parse(&self.url));
+        let mut response = ResolveResponse {
+            resolved_to: String::new(),
+            long_name: url.long_name.clone(),
+            service_name: url.service_name.clone(),
+            path: String::new(),
+            file: None,
+            directory: None,
+        };
+
+        match try!(url.resolve(&params)) {
+            Resolved::File(file, path) => {
+                response.resolved_to = "file".to_string();
+                response.path = path;
+                response.file = Some(try!(file_response::get_response(&file,
+                                                                      params.client.clone(),
+                                                                      self.offset.unwrap_or(0),
+                                                                      self.length.unwrap_or(0),
+                                                                      self.include_metadata
+                                                                          .unwrap_or(false))));
+            }
+            Resolved::Directory(dir, path) => {
+                response.resolved_to = "directory".to_string();
+                response.path = path;
+                response.directory = Some(directory_response::convert_to_response(dir));
+            }
+        }
+
+        Ok(Some(try!(json::encode(&response))))
+    }
+}
+
+// Decodes the `%XX` escapes of `text`, returning None if an escape is malformed or the result is
+// not UTF-8
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let digit = |offset: usize| {
+                bytes.get(index + offset).and_then(|byte| (*byte as char).to_digit(16))
+            };
+            match (digit(1), digit(2)) {
+                (Some(high), Some(low)) => decoded.push((high * 16 + low) as u8),
+                _ => return None,
+            }
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::percent_decode;
+    use Action;
+    use test_utils;
+    use rustc_serialize::json::Json;
+    use dns::register_dns::RegisterDns;
+    use safe_core::core::utility;
+    use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG};
+    use safe_core::nfs::helper::file_helper::FileHelper;
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+    fn parse(url: &str) -> (String, String, Vec<String>) {
+        let url = unwrap_result!(SafeUrl::parse(url));
+        (url.service_name, url.long_name, url.tokens)
+    }
+
+    #[test]
+    fn parse_urls() {
+        let www = "www".to_string();
+        let site = "site".to_string();
+        assert_eq!(parse("safe://site"), (www.clone(), site.clone(), Vec::new()));
+        assert_eq!(parse("SAFE://site/"), (www.clone(), site.clone(), Vec::new()));
+        assert_eq!(parse("safe://blog.site/a/./b%20c/?q=1#top"),
+                   ("blog".to_string(), site.clone(), vec!["a".to_string(), "b c".to_string()]));
+        assert_eq!(parse("safe://blog.my.site"),
+                   ("blog".to_string(), "my.site".to_string(), Vec::new()));
+
+        for url in &["http://site", "safe://", "safe://.site", "safe://blog./a", "safe://site/%zz",
+                     "safe://site/..", "safe://site/a%2Fb"] {
+            assert!(SafeUrl::parse(url).is_err(), "{} parsed", url);
+        }
+
+        assert_eq!(percent_decode("%e2%9C%93"), Some("\u{2713}".to_string()));
+        assert_eq!(percent_decode("%"), None);
+        assert_eq!(percent_decode("%ff"), None);
+    }
+
+    fn get_resolved_to(url: String) -> (String, String) {
+        let parameter_packet = unwrap_result!(test_utils::get_unregistered_parameter_packet());
+        let mut request = Resolve {
+            url: url,
+            offset: None,
+            length: None,
+            include_metadata: None,
+        };
+        let response = unwrap_option!(unwrap_result!(request.execute(parameter_packet)), "");
+        let response = unwrap_result!(Json::from_str(&response));
+        let get = |key: &str| {
+            unwrap_option!(response.find(key).and_then(Json::as_string), "").to_string()
+        };
+        (get("resolved_to"), get("path"))
+    }
+
+    #[test]
+    fn resolve() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+
+        // Creates /site/index.html and /site/docs/readme.txt
+        let mut app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let (mut site, _) = unwrap_result!(dir_helper.create("site".to_string(),
+                                                             UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                             Vec::new(),
+                                                             false,
+                                                             AccessLevel::Public,
+                                                             Some(&mut app_root_dir)));
+        let (docs, _) = unwrap_result!(dir_helper.create("docs".to_string(),
+                                                         UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                         Vec::new(),
+                                                         false,
+                                                         AccessLevel::Public,
+                                                         Some(&mut site)));
+        let writer = unwrap_result!(file_helper.create("readme.txt".to_string(), Vec::new(), docs));
+        let _ = unwrap_result!(writer.close());
+        let site = unwrap_result!(dir_helper.get(site.get_key()));
+        let writer = unwrap_result!(file_helper.create("index.html".to_string(), Vec::new(), site));
+        let _ = unwrap_result!(writer.close());
+
+        let long_name = unwrap_result!(utility::generate_random_string(10));
+        let mut register_request = RegisterDns {
+            long_name: long_name.clone(),
+            service_name: "www".to_string(),
+            is_path_shared: false,
+            service_home_dir_path: "/site".to_string(),
+            extra_owners: None,
+        };
+        assert!(register_request.execute(parameter_packet.clone()).is_ok());
+
+        let file = "file".to_string();
+        let directory = "directory".to_string();
+        assert_eq!(get_resolved_to(format!("safe://{}", long_name)),
+                   (file.clone(), "/index.html".to_string()));
+        assert_eq!(get_resolved_to(format!("safe://www.{}/docs/readme.txt", long_name)),
+                   (file.clone(), "/docs/readme.txt".to_string()));
+        assert_eq!(get_resolved_to(format!("safe://{}/docs/", long_name)),
+                   (directory.clone(), "/docs".to_string()));
+
+        let parameter_packet = unwrap_result!(test_utils::get_unregistered_parameter_packet());
+        let mut request = Resolve {
+            url: format!("safe://{}/missing", long_name),
+            offset: None,
+            length: None,
+            include_metadata: None,
+        };
+        assert!(request.execute(parameter_packet.clone()).is_err());
+        request.url = format!("safe://blog.{}", long_name);
+        assert!(request.execute(parameter_packet).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+use dns::{owners, record};
+use errors::FfiError;
+use routing::StructuredData;
+use rustc_serialize::json;
+use rustc_serialize::base64::FromBase64;
+use maidsafe_utilities::serialisation::deserialise;
+use {ParameterPacket, ResponseType, Action};
+
+/// Signs a change of the owners of `long_name` prepared by another of its owners, `record` being
+/// the record their request handed back
+#[derive(RustcDecodable, Debug)]
+pub struct SignOwnerChange {
+    pub long_name: String,
+    pub record: String,
+}
+
+impl Action for SignOwnerChange {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let updated_record = try!(self.decode_record()
+                                      .map_err(|error| error.with_field("record".to_string())));
+        let response = try!(owners::sign_owner_change(&params, updated_record));
+
+        Ok(Some(try!(json::encode(&response))))
+    }
+}
+
+impl SignOwnerChange {
+    // Decodes the record, which has to be a version of the DNS record of `long_name`
+    fn decode_record(&self) -> Result<StructuredData, FfiError> {
+        let bytes = try!(parse_result!(self.record.from_base64(),
+                                       "Failed Converting from Base64."));
+        let updated_record: StructuredData = try!(deserialise(&bytes));
+        if updated_record.get_type_tag() != record::DNS_TAG ||
+           *updated_record.get_identifier() != record::get_identifier(&self.long_name) {
+            return Err(FfiError::SpecificParseError(format!("Not a DNS record of {:?}",
+                                                            self.long_name)));
+        }
+
+        Ok(updated_record)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Action;
+    use dns::owners::{self, OwnerKey};
+    use dns::remove_owner::RemoveOwner;
+    use dns::register_public_id::RegisterPublicId;
+    use test_utils::get_parameter_packet;
+    use safe_core::core::utility;
+    use rustc_serialize::json::Json;
+    use rustc_serialize::base64::ToBase64;
+
+    #[test]
+    fn sign_owner_change() {
+        let parameter_packet = unwrap_result!(get_parameter_packet(false));
+        let other_packet = unwrap_result!(get_parameter_packet(false));
+        let own_key = unwrap_result!(unwrap_result!(parameter_packet.client.lock())
+                                         .get_public_signing_key())
+                          .clone();
+        let other_public_key = unwrap_result!(unwrap_result!(other_packet.client.lock())
+                                                  .get_public_signing_key())
+                                   .0
+                                   .to_base64(::config::get_base64_config());
+
+        let long_name = unwrap_result!(utility::generate_random_string(10));
+        let mut register_request = RegisterPublicId {
+            long_name: long_name.clone(),
+            extra_owners: Some(vec![OwnerKey { public_key: other_public_key.clone() }]),
+        };
+        assert!(register_request.execute(parameter_packet.clone()).is_ok());
+
+        let mut remove_request = RemoveOwner {
+            long_name: long_name.clone(),
+            public_key: other_public_key,
+        };
+        let response = unwrap_option!(unwrap_result!(remove_request.execute(parameter_packet
+                                                                                .clone())),
+                                      "");
+        let response = unwrap_result!(Json::from_str(&response));
+        let record = unwrap_option!(response.find("record").and_then(Json::as_string), "");
+
+        let mut request = SignOwnerChange {
+            long_name: unwrap_result!(utility::generate_random_string(10)),
+            record: record.to_string(),
+        };
+        assert!(request.execute(other_packet.clone()).is_err());
+        request.long_name = long_name.clone();
+        // Not an owner the change replaces
+        let third_packet = unwrap_result!(get_parameter_packet(false));
+        assert!(request.execute(third_packet).is_err());
+
+        let response = unwrap_option!(unwrap_result!(request.execute(other_packet)), "");
+        let response = unwrap_result!(Json::from_str(&response));
+        assert_eq!(response.find("posted").and_then(Json::as_boolean), Some(true));
+        assert_eq!(unwrap_result!(owners::get_record_owners(&parameter_packet, &long_name)),
+                   vec![own_key]);
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use dns::record;
+use errors::FfiError;
+use rustc_serialize::json;
+use rustc_serialize::base64::ToBase64;
+use maidsafe_utilities::serialisation::serialise;
+use {helper, ParameterPacket, ResponseType, Action};
+use safe_core::dns::errors::DnsError;
+
+#[derive(RustcDecodable, Debug)]
+pub struct UpdateService {
+    pub long_name: String,
+    pub service_name: String,
+    pub is_path_shared: bool,
+    pub service_home_dir_path: String,
+}
+
+/// Serialised key, in base64, of the directory the service pointed to before the update
+#[derive(RustcEncodable, Debug)]
+struct UpdateServiceResponse {
+    previous_home_dir_key: String,
+}
+
+impl Action for UpdateService {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let dir_to_map = try!(helper::get_directory(&params,
+                                                    self.is_path_shared,
+                                                    &self.service_home_dir_path));
+
+        // The service is pointed to the directory within the record itself, so that the record
+        // is updated by a single post and the service never goes missing
+        let current_record = try!(record::get(&params, &self.long_name));
+        let mut contents = try!(record::decode(&params, &current_record));
+        let previous_key = try!(contents.services
+                                        .get(&self.service_name)
+                                        .cloned()
+                                        .ok_or(FfiError::from(DnsError::ServiceNotFound)));
+        if previous_key != *dir_to_map.get_key() {
+            let _ = contents.services.insert(self.service_name.clone(),
+                                             dir_to_map.get_key().clone());
+            try!(record::update(&params, &current_record, &contents));
+        }
+
+        let response = UpdateServiceResponse {
+            previous_home_dir_key: try!(serialise(&previous_key))
+                                       .to_base64(::config::get_base64_config()),
+        };
+        Ok(Some(try!(json::encode(&response))))
+    }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.service_home_dir_path.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use dns::register_dns::RegisterDns;
+    use Action;
+    use test_utils;
+    use rustc_serialize::json::Json;
+    use rustc_serialize::base64::FromBase64;
+    use maidsafe_utilities::serialisation::deserialise;
+    use safe_core::core::utility;
+    use safe_core::dns::dns_operations::DnsOperations;
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+    use safe_core::nfs::metadata::directory_key::DirectoryKey;
+    use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG};
+
+    #[test]
+    fn update_dns_service() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let mut dir_keys = Vec::new();
+        for name in &["old_site", "new_site"] {
+            let mut app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+            let (dir, _) = unwrap_result!(dir_helper.create(name.to_string(),
+                                                            UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                            Vec::new(),
+                                                            false,
+                                                            AccessLevel::Public,
+                                                            Some(&mut app_root_dir)));
+            dir_keys.push(dir.get_key().clone());
+        }
+
+        let public_name = unwrap_result!(utility::generate_random_string(10));
+        let mut register_request = RegisterDns {
+            long_name: public_name.clone(),
+            service_name: "www".to_string(),
+            is_path_shared: false,
+            service_home_dir_path: "/old_site".to_string(),
+            extra_owners: None,
+        };
+        assert!(register_request.execute(parameter_packet.clone()).is_ok());
+
+        let mut request = UpdateService {
+            long_name: public_name.clone(),
+            service_name: "www".to_string(),
+            is_path_shared: false,
+            service_home_dir_path: "/new_site".to_string(),
+        };
+        let response = unwrap_option!(unwrap_result!(request.execute(parameter_packet.clone())),
+                                      "");
+        let response = unwrap_result!(Json::from_str(&response));
+        let previous_key = unwrap_option!(response.find("previous_home_dir_key")
+                                                  .and_then(Json::as_string),
+                                          "");
+        let previous_key: DirectoryKey =
+            unwrap_result!(deserialise(&unwrap_result!(previous_key.from_base64())));
+        assert_eq!(previous_key, dir_keys[0]);
+
+        let dns_operations = unwrap_result!(DnsOperations::new(parameter_packet.client.clone()));
+        let current_key =
+            unwrap_result!(dns_operations.get_service_home_directory_key(&public_name,
+                                                                         &request.service_name,
+                                                                         None));
+        assert_eq!(current_key, dir_keys[1]);
+
+        request.service_name = "blog".to_string();
+        assert!(request.execute(parameter_packet.clone()).is_err());
+        request.service_name = "www".to_string();
+        request.service_home_dir_path = "/missing".to_string();
+        assert!(request.execute(parameter_packet).is_err());
+    }
+}
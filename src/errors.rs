@@ -19,11 +19,11 @@ use std::fmt;
 
 use rustc_serialize::{json, base64};
 
-use safe_core::core::errors::CoreError;
+use safe_core::core::errors::{CLIENT_ERROR_START_RANGE, CoreError};
 use safe_core::dns::errors::{DNS_ERROR_START_RANGE, DnsError};
-use safe_core::nfs::errors::NfsError;
+use safe_core::nfs::errors::{NFS_ERROR_START_RANGE, NfsError};
 
-use maidsafe_utilities::serialisation::SerialisationError;
+use maidsafe_utilities::serialisation::{self, SerialisationError};
 
 /// Intended for converting Launcher Errors into numeric codes for propagating some error
 /// information across FFI boundaries and specially to C.
@@ -60,6 +60,149 @@ pub enum FfiError {
     Unexpected(String),
     /// Could not serialise or deserialise data
     UnsuccessfulEncodeDecode(SerialisationError),
+    /// Asynchronous request was cancelled before it was executed
+    RequestCancelled,
+    /// No queued asynchronous request with the given id - it may already be executing or be done
+    InvalidRequestId,
+    /// Requested version does not exist in the history of the file or directory
+    VersionNotFound,
+    /// A segment of the supplied path or a new name broke the naming rules - describes which
+    /// segment and why
+    InvalidPathSegment(String),
+    /// Undoing the effects of a failed operation failed in turn, leaving data inconsistent
+    RollbackFailed {
+        /// What was left inconsistent
+        inconsistent: String,
+        /// Error the operation failed with in the first place
+        cause: Box<FfiError>,
+    },
+    /// Function called from within a completion callback of the client it operates on, which it
+    /// would wait on forever
+    CalledFromCallback,
+    /// Another error along with the field of the request which caused it and the path it occurred
+    /// on, as far as known. Has the code of the wrapped error.
+    WithDetails {
+        /// The error itself
+        error: Box<FfiError>,
+        /// Field of the request which was missing or malformed
+        field: Option<String>,
+        /// Path the request failed on
+        path: Option<String>,
+    },
+}
+
+impl FfiError {
+    /// Reports that undoing the effects of an operation which failed with `cause` failed in turn,
+    /// leaving `inconsistent` in an inconsistent state
+    pub fn rollback_failed(inconsistent: String, cause: FfiError) -> FfiError {
+        FfiError::RollbackFailed {
+            inconsistent: inconsistent,
+            cause: Box::new(cause),
+        }
+    }
+
+    /// Attaches the field of the request which caused this error, unless one is attached already
+    pub fn with_field(self, field: String) -> FfiError {
+        self.with_details(Some(field), None)
+    }
+
+    /// Attaches the path this error occurred on, unless one is attached already
+    pub fn with_path(self, path: String) -> FfiError {
+        self.with_details(None, Some(path))
+    }
+
+    fn with_details(self, new_field: Option<String>, new_path: Option<String>) -> FfiError {
+        match self {
+            FfiError::WithDetails { error, field, path } => {
+                FfiError::WithDetails {
+                    error: error,
+                    field: field.or(new_field),
+                    path: path.or(new_path),
+                }
+            }
+            error => {
+                FfiError::WithDetails {
+                    error: Box::new(error),
+                    field: new_field,
+                    path: new_path,
+                }
+            }
+        }
+    }
+}
+
+/// Details of a failed call, kept per client handle so that callers can find out more about an
+/// error than its numeric code.
+#[derive(RustcEncodable, Debug)]
+pub struct ErrorDetails {
+    /// Name of the `FfiError` variant
+    pub category: String,
+    /// Numeric code returned by the failed call
+    pub code: i32,
+    /// Human readable description of the error
+    pub description: String,
+    /// JSON field which was missing from or malformed in the request, if known
+    pub field: Option<String>,
+    /// Path the failed request operated on, if known
+    pub path: Option<String>,
+}
+
+impl From<FfiError> for ErrorDetails {
+    fn from(error: FfiError) -> ErrorDetails {
+        let (error, field, path) = match error {
+            FfiError::WithDetails { error, field, path } => (*error, field, path),
+            error => (error, None, None),
+        };
+        let field = field.or_else(|| {
+            match error {
+                FfiError::JsonDecodeError(json::DecoderError::MissingFieldError(ref field)) => {
+                    Some(field.clone())
+                }
+                _ => None,
+            }
+        });
+        let category = match error {
+            FfiError::CoreError(_) => "CoreError",
+            FfiError::NfsError(_) => "NfsError",
+            FfiError::DnsError(_) => "DnsError",
+            FfiError::PathNotFound => "PathNotFound",
+            FfiError::InvalidPath => "InvalidPath",
+            FfiError::PermissionDenied => "PermissionDenied",
+            FfiError::JsonParseError(_) => "JsonParseError",
+            FfiError::JsonDecodeError(_) => "JsonDecodeError",
+            FfiError::SpecificParseError(_) => "SpecificParseError",
+            FfiError::JsonEncodeError(_) => "JsonEncodeError",
+            FfiError::LocalConfigAccessFailed(_) => "LocalConfigAccessFailed",
+            FfiError::Unexpected(_) => "Unexpected",
+            FfiError::UnsuccessfulEncodeDecode(_) => "UnsuccessfulEncodeDecode",
+            FfiError::RequestCancelled => "RequestCancelled",
+            FfiError::InvalidRequestId => "InvalidRequestId",
+            FfiError::VersionNotFound => "VersionNotFound",
+            FfiError::InvalidPathSegment(_) => "InvalidPathSegment",
+            FfiError::RollbackFailed { .. } => "RollbackFailed",
+            FfiError::CalledFromCallback => "CalledFromCallback",
+            // Never nested, as attaching details to an error which has some merges them
+            FfiError::WithDetails { .. } => "WithDetails",
+        };
+        let description = match error {
+            FfiError::SpecificParseError(ref message) |
+            FfiError::LocalConfigAccessFailed(ref message) |
+            FfiError::Unexpected(ref message) |
+            FfiError::InvalidPathSegment(ref message) => message.clone(),
+            FfiError::RollbackFailed { ref inconsistent, ref cause } => {
+                format!("{} left inconsistent after {:?}", inconsistent, cause)
+            }
+            _ => format!("{:?}", error),
+        };
+
+        ErrorDetails {
+            category: category.to_string(),
+            code: error.into(),
+            description: description,
+            field: field,
+            path: path,
+        }
+    }
 }
 
 impl From<SerialisationError> for FfiError {
@@ -131,6 +274,13 @@ impl Into<i32> for FfiError {
             FfiError::LocalConfigAccessFailed(_) => FFI_ERROR_START_RANGE - 8,
             FfiError::Unexpected(_) => FFI_ERROR_START_RANGE - 9,
             FfiError::UnsuccessfulEncodeDecode(_) => FFI_ERROR_START_RANGE - 10,
+            FfiError::RequestCancelled => FFI_ERROR_START_RANGE - 11,
+            FfiError::InvalidRequestId => FFI_ERROR_START_RANGE - 12,
+            FfiError::VersionNotFound => FFI_ERROR_START_RANGE - 13,
+            FfiError::InvalidPathSegment(_) => FFI_ERROR_START_RANGE - 14,
+            FfiError::RollbackFailed { .. } => FFI_ERROR_START_RANGE - 15,
+            FfiError::CalledFromCallback => FFI_ERROR_START_RANGE - 16,
+            FfiError::WithDetails { error, .. } => (*error).into(),
         }
     }
 }
@@ -163,6 +313,347 @@ impl fmt::Debug for FfiError {
             FfiError::UnsuccessfulEncodeDecode(ref err) => {
                 write!(f, "FfiError::UnsuccessfulEncodeDecode -> {:?}", err)
             }
+            FfiError::RequestCancelled => write!(f, "FfiError::RequestCancelled"),
+            FfiError::InvalidRequestId => write!(f, "FfiError::InvalidRequestId"),
+            FfiError::VersionNotFound => write!(f, "FfiError::VersionNotFound"),
+            FfiError::InvalidPathSegment(ref error) => {
+                write!(f, "FfiError::InvalidPathSegment -> {:?}", error)
+            }
+            FfiError::RollbackFailed { ref inconsistent, ref cause } => {
+                write!(f,
+                       "FfiError::RollbackFailed -> {:?} left inconsistent after {:?}",
+                       inconsistent,
+                       cause)
+            }
+            FfiError::CalledFromCallback => write!(f, "FfiError::CalledFromCallback"),
+            FfiError::WithDetails { ref error, ref field, ref path } => {
+                write!(f, "{:?} (field: {:?}, path: {:?})", error, field, path)
+            }
+        }
+    }
+}
+
+/// Name, owning module and description of an error code returned by this crate
+#[derive(RustcEncodable, Debug, Clone, PartialEq)]
+pub struct ErrorCodeInfo {
+    /// Numeric code
+    pub code: i32,
+    /// Name of the error variant
+    pub name: String,
+    /// Module owning the error
+    pub module: String,
+    /// What the error means
+    pub description: String,
+}
+
+// Offsets from `FFI_ERROR_START_RANGE`, names and descriptions of the codes owned by this crate.
+// Must be kept in line with `impl Into<i32> for FfiError`.
+const FFI_ERROR_CODES: [(i32, &'static str, &'static str); 16] =
+    [(1, "PathNotFound", "Unable to find/traverse directory or file path"),
+     (2, "InvalidPath", "Supplied path was invalid"),
+     (3, "PermissionDenied", "Permission denied - e.g. permission to access SAFEDrive etc."),
+     (4, "JsonParseError", "Could not parse payload as a valid JSON"),
+     (5, "JsonDecodeError", "Could not decode valid JSON into expected Structures"),
+     (6,
+      "SpecificParseError",
+      "JSON non-conforming to the Launcher RFC, e.g. invalid base64 formatting or ranges"),
+     (7, "JsonEncodeError", "Error encoding into Json String"),
+     (8, "LocalConfigAccessFailed", "Unable to Read from or Write to a Local Config file"),
+     (9, "Unexpected", "Unexpected - Probably a Logic error"),
+     (10, "UnsuccessfulEncodeDecode", "Could not serialise or deserialise data"),
+     (11, "RequestCancelled", "Asynchronous request was cancelled before it was executed"),
+     (12, "InvalidRequestId", "No queued asynchronous request with the given id"),
+     (13,
+      "VersionNotFound",
+      "Requested version does not exist in the history of the file or directory"),
+     (14,
+      "InvalidPathSegment",
+      "A path segment or name was empty, too long or contained invalid characters"),
+     (15,
+      "RollbackFailed",
+      "Undoing the effects of a failed operation failed, leaving data inconsistent"),
+     (16,
+      "CalledFromCallback",
+      "Function called from within a completion callback of the client it operates on")];
+
+/// Returns all the error codes this crate may return along with their names, owning modules and
+/// descriptions. Codes of safe_core which are not listed individually are described by
+/// `describe_error_code` according to the range they fall in.
+pub fn get_error_codes() -> Vec<ErrorCodeInfo> {
+    fn info<E: Into<i32>>(error: E, name: &str, module: &str, description: &str) -> ErrorCodeInfo {
+        ErrorCodeInfo {
+            code: error.into(),
+            name: name.to_string(),
+            module: module.to_string(),
+            description: description.to_string(),
+        }
+    }
+
+    let mut codes = FFI_ERROR_CODES.iter()
+                                   .map(|&(offset, name, description)| {
+                                       ErrorCodeInfo {
+                                           code: FFI_ERROR_START_RANGE - offset,
+                                           name: name.to_string(),
+                                           module: "safe_ffi".to_string(),
+                                           description: description.to_string(),
+                                       }
+                                   })
+                                   .collect::<Vec<_>>();
+
+    let nfs = "safe_core::nfs";
+    codes.push(info(NfsError::AlreadyExists, "AlreadyExists", nfs, "Item already exists"));
+    codes.push(info(NfsError::DestinationAndSourceAreSame,
+                    "DestinationAndSourceAreSame",
+                    nfs,
+                    "Destination is the same as the source"));
+    codes.push(info(NfsError::DirectoryAlreadyExistsWithSameName,
+                    "DirectoryAlreadyExistsWithSameName",
+                    nfs,
+                    "A directory with the same name already exists"));
+    codes.push(info(NfsError::DirectoryNotFound,
+                    "DirectoryNotFound",
+                    nfs,
+                    "Directory not found"));
+    codes.push(info(NfsError::FailedToUpdateDirectory,
+                    "FailedToUpdateDirectory",
+                    nfs,
+                    "Failed to update the directory listing"));
+    codes.push(info(NfsError::FailedToUpdateFile,
+                    "FailedToUpdateFile",
+                    nfs,
+                    "Failed to update the file"));
+    codes.push(info(NfsError::FileAlreadyExistsWithSameName,
+                    "FileAlreadyExistsWithSameName",
+                    nfs,
+                    "A file with the same name already exists"));
+    codes.push(info(NfsError::FileDoesNotMatch,
+                    "FileDoesNotMatch",
+                    nfs,
+                    "File does not match the one in the directory listing"));
+    codes.push(info(NfsError::FileNotFound, "FileNotFound", nfs, "File not found"));
+    codes.push(info(NfsError::InvalidRangeSpecified,
+                    "InvalidRangeSpecified",
+                    nfs,
+                    "Invalid byte range specified"));
+    codes.push(info(NfsError::ParameterIsNotValid,
+                    "ParameterIsNotValid",
+                    nfs,
+                    "Invalid parameter"));
+    codes.push(info(NfsError::Unexpected(String::new()),
+                    "Unexpected",
+                    nfs,
+                    "Unexpected - Probably a Logic error"));
+    codes.push(info(NfsError::UnsuccessfulEncodeDecode(serialisation_error()),
+                    "UnsuccessfulEncodeDecode",
+                    nfs,
+                    "Could not serialise or deserialise data"));
+
+    let dns = "safe_core::dns";
+    codes.push(info(DnsError::DnsNameAlreadyRegistered,
+                    "DnsNameAlreadyRegistered",
+                    dns,
+                    "Long name is already registered"));
+    codes.push(info(DnsError::DnsRecordNotFound,
+                    "DnsRecordNotFound",
+                    dns,
+                    "No record found for the long name"));
+    codes.push(info(DnsError::ServiceAlreadyExists,
+                    "ServiceAlreadyExists",
+                    dns,
+                    "Service already exists for the long name"));
+    codes.push(info(DnsError::ServiceNotFound,
+                    "ServiceNotFound",
+                    dns,
+                    "Service not found for the long name"));
+    codes.push(info(DnsError::DnsConfigFileNotFoundOrCorrupted,
+                    "DnsConfigFileNotFoundOrCorrupted",
+                    dns,
+                    "DNS configuration file is missing or corrupt"));
+    codes.push(info(DnsError::Unexpected(String::new()),
+                    "Unexpected",
+                    dns,
+                    "Unexpected - Probably a Logic error"));
+    codes.push(info(DnsError::UnsuccessfulEncodeDecode(serialisation_error()),
+                    "UnsuccessfulEncodeDecode",
+                    dns,
+                    "Could not serialise or deserialise data"));
+
+    let core = "safe_core::core";
+    codes.push(info(CoreError::StructuredDataHeaderSizeProhibitive,
+                    "StructuredDataHeaderSizeProhibitive",
+                    core,
+                    "Structured data is too big to be stored"));
+    codes.push(info(CoreError::UnsuccessfulEncodeDecode(serialisation_error()),
+                    "UnsuccessfulEncodeDecode",
+                    core,
+                    "Could not serialise or deserialise data"));
+    codes.push(info(CoreError::AsymmetricDecipherFailure,
+                    "AsymmetricDecipherFailure",
+                    core,
+                    "Asymmetric decryption failed"));
+    codes.push(info(CoreError::SymmetricDecipherFailure,
+                    "SymmetricDecipherFailure",
+                    core,
+                    "Symmetric decryption failed"));
+    codes.push(info(CoreError::ReceivedUnexpectedData,
+                    "ReceivedUnexpectedData",
+                    core,
+                    "Received data of an unexpected type"));
+    codes.push(info(CoreError::VersionCacheMiss,
+                    "VersionCacheMiss",
+                    core,
+                    "No such data found in the version cache"));
+    codes.push(info(CoreError::RootDirectoryAlreadyExists,
+                    "RootDirectoryAlreadyExists",
+                    core,
+                    "Root directory of the user already exists"));
+    codes.push(info(CoreError::RandomDataGenerationFailure,
+                    "RandomDataGenerationFailure",
+                    core,
+                    "Could not generate random data"));
+    codes.push(info(CoreError::OperationForbiddenForClient,
+                    "OperationForbiddenForClient",
+                    core,
+                    "Operation is not allowed for an unregistered client"));
+    codes.push(info(CoreError::Unexpected(String::new()),
+                    "Unexpected",
+                    core,
+                    "Unexpected - Probably a Logic error"));
+
+    codes
+}
+
+// A serialisation error, to take the codes of the variants wrapping one from
+fn serialisation_error() -> SerialisationError {
+    unwrap_option!(serialisation::deserialise::<u64>(&[]).err(),
+                   "Deserialised a u64 from no bytes")
+}
+
+/// Returns the name, owning module and description of any code returned by this crate, or `None`
+/// if the code is not one of them. Codes of safe_core which are not listed by `get_error_codes`
+/// are described by the module owning their range.
+pub fn describe_error_code(code: i32) -> Option<ErrorCodeInfo> {
+    if let Some(info) = get_error_codes().into_iter().find(|info| info.code == code) {
+        return Some(info);
+    }
+
+    let (name, module) = if code <= CLIENT_ERROR_START_RANGE && code > NFS_ERROR_START_RANGE {
+        ("CoreError", "safe_core::core")
+    } else if code <= NFS_ERROR_START_RANGE && code > DNS_ERROR_START_RANGE {
+        ("NfsError", "safe_core::nfs")
+    } else if code <= DNS_ERROR_START_RANGE && code > FFI_ERROR_START_RANGE {
+        ("DnsError", "safe_core::dns")
+    } else {
+        return None;
+    };
+
+    Some(ErrorCodeInfo {
+        code: code,
+        name: name.to_string(),
+        module: module.to_string(),
+        description: format!("Error of the {} module", module),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::serialisation_error;
+
+    use rustc_serialize::json;
+
+    use safe_core::core::errors::CoreError;
+    use safe_core::dns::errors::DnsError;
+    use safe_core::nfs::errors::NfsError;
+
+    #[test]
+    fn error_code_descriptions() {
+        let codes = get_error_codes();
+        for info in &codes {
+            assert_eq!(codes.iter().filter(|other| other.code == info.code).count(), 1);
+            assert_eq!(describe_error_code(info.code).as_ref(), Some(info));
+        }
+
+        let code: i32 = FfiError::PermissionDenied.into();
+        let info = unwrap_option!(describe_error_code(code), "");
+        assert_eq!(info.name, "PermissionDenied");
+        assert_eq!(info.module, "safe_ffi");
+
+        let code: i32 = FfiError::VersionNotFound.into();
+        assert_eq!(unwrap_option!(describe_error_code(code), "").name,
+                   "VersionNotFound");
+
+        assert!(describe_error_code(1).is_none());
+        assert!(describe_error_code(FFI_ERROR_START_RANGE - 100).is_none());
+    }
+
+    // Fails to compile once a variant is added, as a reminder to list it in `error_code_names`
+    #[allow(unused)]
+    fn all_variants_listed(error: FfiError) {
+        match error {
+            FfiError::CoreError(_) |
+            FfiError::NfsError(_) |
+            FfiError::DnsError(_) |
+            FfiError::PathNotFound |
+            FfiError::InvalidPath |
+            FfiError::PermissionDenied |
+            FfiError::JsonParseError(_) |
+            FfiError::JsonDecodeError(_) |
+            FfiError::SpecificParseError(_) |
+            FfiError::JsonEncodeError(_) |
+            FfiError::LocalConfigAccessFailed(_) |
+            FfiError::Unexpected(_) |
+            FfiError::UnsuccessfulEncodeDecode(_) |
+            FfiError::RequestCancelled |
+            FfiError::InvalidRequestId |
+            FfiError::VersionNotFound |
+            FfiError::InvalidPathSegment(_) |
+            FfiError::RollbackFailed { .. } |
+            FfiError::CalledFromCallback |
+            FfiError::WithDetails { .. } => (),
+        }
+    }
+
+    #[test]
+    fn error_code_names() {
+        let errors =
+            vec![(FfiError::from(CoreError::Unexpected(String::new())), "Unexpected"),
+                 (FfiError::from(CoreError::VersionCacheMiss), "VersionCacheMiss"),
+                 (FfiError::from(CoreError::UnsuccessfulEncodeDecode(serialisation_error())),
+                  "UnsuccessfulEncodeDecode"),
+                 (FfiError::from(NfsError::FileNotFound), "FileNotFound"),
+                 (FfiError::from(NfsError::UnsuccessfulEncodeDecode(serialisation_error())),
+                  "UnsuccessfulEncodeDecode"),
+                 (FfiError::from(DnsError::ServiceNotFound), "ServiceNotFound"),
+                 (FfiError::PathNotFound, "PathNotFound"),
+                 (FfiError::InvalidPath, "InvalidPath"),
+                 (FfiError::PermissionDenied, "PermissionDenied"),
+                 (FfiError::from(unwrap_option!(json::Json::from_str("{").err(), "")),
+                  "JsonParseError"),
+                 (FfiError::from(json::DecoderError::MissingFieldError(String::new())),
+                  "JsonDecodeError"),
+                 (FfiError::SpecificParseError(String::new()), "SpecificParseError"),
+                 (FfiError::from(json::EncoderError::BadHashmapKey), "JsonEncodeError"),
+                 (FfiError::LocalConfigAccessFailed(String::new()), "LocalConfigAccessFailed"),
+                 (FfiError::Unexpected(String::new()), "Unexpected"),
+                 (FfiError::from(serialisation_error()), "UnsuccessfulEncodeDecode"),
+                 (FfiError::RequestCancelled, "RequestCancelled"),
+                 (FfiError::InvalidRequestId, "InvalidRequestId"),
+                 (FfiError::VersionNotFound, "VersionNotFound"),
+                 (FfiError::InvalidPathSegment(String::new()), "InvalidPathSegment"),
+                 (FfiError::rollback_failed(String::new(), FfiError::PathNotFound),
+                  "RollbackFailed"),
+                 (FfiError::CalledFromCallback, "CalledFromCallback"),
+                 (FfiError::PathNotFound.with_path("/a".to_string()), "PathNotFound")];
+
+        for (error, name) in errors {
+            let description = format!("{:?}", error);
+            let info = unwrap_option!(describe_error_code(error.into()), "");
+            assert!(info.name == name,
+                    "{} is described as {}, not {}",
+                    description,
+                    info.name,
+                    name);
         }
     }
 }
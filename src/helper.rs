@@ -15,20 +15,31 @@
 // Please review the Licences for the specific language governing permissions and limitations
 // relating to use of the SAFE Network Software.
 
+use std::cmp;
+use std::panic;
 use std::error::Error;
 use std::sync::{Arc, Mutex};
 
 use libc::c_char;
 use std::ffi::CStr;
 use errors::FfiError;
+use {path, Action, ParameterPacket};
+use rustc_serialize::{Decodable, Decoder};
+use rustc_serialize::json::{self, Json};
+use meta::schema::{self, ActionSchema, FieldSchema};
+use safe_core::nfs::file::File;
 use safe_core::nfs::AccessLevel;
 use safe_core::core::client::Client;
 use config::SAFE_DRIVE_DIR_NAME;
 use safe_core::nfs::UNVERSIONED_DIRECTORY_LISTING_TAG;
 use safe_core::nfs::directory_listing::DirectoryListing;
 use safe_core::nfs::metadata::directory_key::DirectoryKey;
+use safe_core::nfs::helper::writer::Writer;
+use safe_core::nfs::helper::file_helper::FileHelper;
 use safe_core::nfs::helper::directory_helper::DirectoryHelper;
 
+const COPY_CHUNK_SIZE: u64 = 1024 * 1024;
+
 #[allow(unsafe_code)]
 pub fn c_char_ptr_to_string(c_char_ptr: *const c_char) -> Result<String, FfiError> {
     let cstr = unsafe { CStr::from_ptr(c_char_ptr) };
@@ -36,11 +47,79 @@ pub fn c_char_ptr_to_string(c_char_ptr: *const c_char) -> Result<String, FfiErro
                 .map_err(|error| FfiError::from(error.description()))))
 }
 
-pub fn tokenise_path(path: &str, keep_empty_splits: bool) -> Vec<String> {
-    path.split(|element| element == '/')
-        .filter(|token| keep_empty_splits || token.len() != 0)
-        .map(|token| token.to_string())
-        .collect()
+/// Decodes the `data` of `request`. A failure names the field at fault: the first of `fields`
+/// which the data does not match or else the field the decoder found missing.
+pub fn decode_data<T: Decodable>(request: &Json, fields: &[FieldSchema]) -> Result<T, FfiError> {
+    let mut decoder = json::Decoder::new(request.clone());
+    decoder.read_struct_field("data", 0, |d| T::decode(d)).map_err(|error| {
+        let field = match request.find("data") {
+            Some(data) => schema::find_mismatched_field(fields, data),
+            None => Some("data".to_string()),
+        };
+        let field = field.or_else(|| {
+            match error {
+                json::DecoderError::MissingFieldError(ref field) => Some(field.clone()),
+                _ => None,
+            }
+        });
+
+        let error = FfiError::SpecificParseError(format!("{:?}", error));
+        match field {
+            Some(field) => error.with_field(field),
+            None => error,
+        }
+    })
+}
+
+/// Decodes the `data` of `request` as in `decode_data` into the action to execute
+pub fn decode_action<T>(request: &Json, fields: &[FieldSchema]) -> Result<Box<Action>, FfiError>
+    where T: Action + Decodable + 'static
+{
+    Ok(Box::new(try!(decode_data::<T>(request, fields))))
+}
+
+/// Decodes a request into its action, given the fields described for the action
+pub type ActionDecoder = fn(&Json, &[FieldSchema]) -> Result<Box<Action>, FfiError>;
+
+/// Entry of the table of actions of a module, which both the schemas of the module and the
+/// decoding of its requests are taken from
+pub fn describe_action<T>(name: &'static str,
+                          fields: Vec<FieldSchema>)
+                          -> (ActionSchema, ActionDecoder)
+    where T: Action + Decodable + 'static
+{
+    (ActionSchema::new(name, fields), decode_action::<T>)
+}
+
+/// Decodes `request` into the action named `action` in the table `actions` of a module
+pub fn find_action(actions: Vec<(ActionSchema, ActionDecoder)>,
+                   action: String,
+                   request: &Json)
+                   -> Result<Box<Action>, FfiError> {
+    match actions.into_iter().find(|&(ref schema, _)| schema.name == action) {
+        Some((schema, decode)) => decode(request, &schema.fields),
+        None => {
+            Err(FfiError::SpecificParseError(format!("Unsupported action {:?} for this endpoint.",
+                                                     action)))
+        }
+    }
+}
+
+/// Runs `f`, turning a panic into `FfiError::Unexpected` so that it never unwinds into the caller
+/// of an FFI function
+pub fn catch_unwind<F, T>(f: F) -> Result<T, FfiError>
+    where F: FnOnce() -> T
+{
+    panic::catch_unwind(panic::AssertUnwindSafe(f)).map_err(|payload| {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Unknown cause".to_string()
+        };
+        FfiError::Unexpected(format!("Panicked: {}", message))
+    })
 }
 
 pub fn get_safe_drive_key(client: Arc<Mutex<Client>>) -> Result<DirectoryKey, FfiError> {
@@ -64,26 +143,213 @@ pub fn get_safe_drive_key(client: Arc<Mutex<Client>>) -> Result<DirectoryKey, Ff
     Ok(key)
 }
 
-pub fn get_final_subdirectory(client: Arc<Mutex<Client>>,
+/// Key of the directory the paths of a request start from: SAFEDrive for shared paths and the root
+/// directory of the application otherwise. Fails with `PermissionDenied` for shared paths if the
+/// application has no access to SAFEDrive.
+pub fn get_start_dir_key(params: &ParameterPacket,
+                         is_path_shared: bool)
+                         -> Result<DirectoryKey, FfiError> {
+    if is_path_shared && !params.safe_drive_access {
+        return Err(FfiError::PermissionDenied);
+    }
+
+    if is_path_shared {
+        params.safe_drive_dir_key
+              .clone()
+              .ok_or(FfiError::from("Safe Drive directory key is not present"))
+    } else {
+        params.app_root_dir_key
+              .clone()
+              .ok_or(FfiError::from("Application directory key is not present"))
+    }
+}
+
+/// Fetches the listing of the directory at `dir_path` for a read-only action
+pub fn get_directory(params: &ParameterPacket,
+                     is_path_shared: bool,
+                     dir_path: &str)
+                     -> Result<DirectoryListing, FfiError> {
+    let start_dir_key = try!(get_start_dir_key(params, is_path_shared));
+    let tokens = try!(path::tokenise(dir_path));
+    get_final_subdirectory(params, &tokens, Some(&start_dir_key))
+}
+
+/// Fetches the listing of the directory at `dir_path` for an action modifying it, as in
+/// `get_final_subdirectory_to_modify`
+pub fn get_directory_to_modify(params: &ParameterPacket,
+                               is_path_shared: bool,
+                               dir_path: &str)
+                               -> Result<DirectoryListing, FfiError> {
+    let start_dir_key = try!(get_start_dir_key(params, is_path_shared));
+    let tokens = try!(path::tokenise(dir_path));
+    get_final_subdirectory_to_modify(params, &tokens, Some(&start_dir_key))
+}
+
+/// Fetches the listing of the directory holding the file at `file_path` for a read-only action,
+/// along with the name of the file
+pub fn get_directory_and_file(params: &ParameterPacket,
+                              is_path_shared: bool,
+                              file_path: &str)
+                              -> Result<(DirectoryListing, String), FfiError> {
+    let start_dir_key = try!(get_start_dir_key(params, is_path_shared));
+    let (tokens, file_name) = try!(path::split_last(file_path));
+    let directory = try!(get_final_subdirectory(params, &tokens, Some(&start_dir_key)));
+    Ok((directory, file_name))
+}
+
+/// Fetches the listing of the directory holding the file at `file_path` for an action modifying
+/// the file or the directory, as in `get_final_subdirectory_to_modify`, along with the name of
+/// the file
+pub fn get_directory_and_file_to_modify(params: &ParameterPacket,
+                                        is_path_shared: bool,
+                                        file_path: &str)
+                                        -> Result<(DirectoryListing, String), FfiError> {
+    let start_dir_key = try!(get_start_dir_key(params, is_path_shared));
+    let (tokens, file_name) = try!(path::split_last(file_path));
+    let directory = try!(get_final_subdirectory_to_modify(params, &tokens, Some(&start_dir_key)));
+    Ok((directory, file_name))
+}
+
+/// Writes the content of `file` through `writer` a chunk at a time, so that the content is
+/// re-encrypted into the writer's datamap without ever being held in memory as a whole
+pub fn copy_file_content(client: Arc<Mutex<Client>>,
+                         file: &File,
+                         writer: &mut Writer)
+                         -> Result<(), FfiError> {
+    let file_helper = FileHelper::new(client);
+    let mut reader = file_helper.read(file);
+    let size = reader.size();
+    let mut offset = 0;
+    while offset < size {
+        let length = cmp::min(COPY_CHUNK_SIZE, size - offset);
+        writer.write(&try!(reader.read(offset, length)), offset);
+        offset += length;
+    }
+
+    Ok(())
+}
+
+/// Walks `tokens` from `starting_directory`, or from the root directory of the user if `None`,
+/// taking the listings from the directory cache of the client. For read-only actions only, as the
+/// listings may be stale.
+pub fn get_final_subdirectory(params: &ParameterPacket,
                               tokens: &Vec<String>,
                               starting_directory: Option<&DirectoryKey>)
                               -> Result<DirectoryListing, FfiError> {
-    let dir_helper = DirectoryHelper::new(client);
+    walk_subdirectories(params, tokens, starting_directory, false)
+}
+
+/// Walks `tokens` as in `get_final_subdirectory`, but fetches the final directory from the
+/// network rather than the cache. Updating a stale listing would overwrite the changes other
+/// clients made to the directory since it was cached, so the actions modifying the final
+/// directory or the files in it must use this.
+pub fn get_final_subdirectory_to_modify(params: &ParameterPacket,
+                                        tokens: &Vec<String>,
+                                        starting_directory: Option<&DirectoryKey>)
+                                        -> Result<DirectoryListing, FfiError> {
+    walk_subdirectories(params, tokens, starting_directory, true)
+}
+
+fn walk_subdirectories(params: &ParameterPacket,
+                       tokens: &Vec<String>,
+                       starting_directory: Option<&DirectoryKey>,
+                       is_final_fetched: bool)
+                       -> Result<DirectoryListing, FfiError> {
+    let dir_helper = DirectoryHelper::new(params.client.clone());
+    let get_listing = |directory_key: &DirectoryKey, is_final: bool| {
+        if is_final && is_final_fetched {
+            get_fresh_directory(params, &dir_helper, directory_key)
+        } else {
+            get_cached_directory(params, &dir_helper, directory_key)
+        }
+    };
 
     let mut current_dir_listing = match starting_directory {
-        Some(directory_key) => try!(dir_helper.get(directory_key)),
+        Some(directory_key) => try!(get_listing(directory_key, tokens.is_empty())),
         None => try!(dir_helper.get_user_root_directory_listing()),
     };
 
-    for it in tokens.iter() {
+    for (index, it) in tokens.iter().enumerate() {
         current_dir_listing = {
             let current_dir_metadata = try!(current_dir_listing.get_sub_directories()
                                                                .iter()
                                                                .find(|a| *a.get_name() == *it)
                                                                .ok_or(FfiError::PathNotFound));
-            try!(dir_helper.get(current_dir_metadata.get_key()))
+            try!(get_listing(current_dir_metadata.get_key(), index + 1 == tokens.len()))
         };
     }
 
     Ok(current_dir_listing)
 }
+
+/// Fetches the listing of the directory through the directory cache of the client
+pub fn get_cached_directory(params: &ParameterPacket,
+                            dir_helper: &DirectoryHelper,
+                            directory_key: &DirectoryKey)
+                            -> Result<DirectoryListing, FfiError> {
+    if let Some(listing) = unwrap_result!(params.directory_cache.lock()).get(directory_key) {
+        return Ok(listing);
+    }
+
+    get_fresh_directory(params, dir_helper, directory_key)
+}
+
+/// Fetches the listing of the directory from the network, refreshing the directory cache of the
+/// client with it
+fn get_fresh_directory(params: &ParameterPacket,
+                       dir_helper: &DirectoryHelper,
+                       directory_key: &DirectoryKey)
+                       -> Result<DirectoryListing, FfiError> {
+    let listing = try!(dir_helper.get(directory_key));
+    unwrap_result!(params.directory_cache.lock()).insert(listing.clone());
+    Ok(listing)
+}
+
+/// Drops `directory` and its parent from the directory cache of the client. Must be called after
+/// each change made to a directory or to the files it holds.
+pub fn invalidate_cached_directory(params: &ParameterPacket, directory: &DirectoryListing) {
+    unwrap_result!(params.directory_cache.lock()).invalidate(directory);
+}
+
+/// Walks `tokens` from `starting_directory` in the same way as `get_final_subdirectory`, but
+/// creates every directory which is missing on the way instead of failing. Returns the final
+/// directory along with the paths, relative to `starting_directory`, of the directories created.
+/// As any directory on the way may be updated with a directory created in it, they are all
+/// fetched from the network rather than the cache.
+pub fn get_or_create_final_subdirectory(params: &ParameterPacket,
+                                        tokens: &Vec<String>,
+                                        starting_directory: &DirectoryKey,
+                                        tag: u64,
+                                        is_versioned: bool,
+                                        access_level: AccessLevel)
+                                        -> Result<(DirectoryListing, Vec<String>), FfiError> {
+    let dir_helper = DirectoryHelper::new(params.client.clone());
+
+    let mut current_dir_listing = try!(get_fresh_directory(params,
+                                                           &dir_helper,
+                                                           starting_directory));
+    let mut current_path = String::new();
+    let mut created_paths = Vec::new();
+
+    for it in tokens.iter() {
+        current_path = format!("{}/{}", current_path, it);
+        let existing_dir_key = current_dir_listing.find_sub_directory(it)
+                                                  .map(|metadata| metadata.get_key().clone());
+        current_dir_listing = match existing_dir_key {
+            Some(dir_key) => try!(get_fresh_directory(params, &dir_helper, &dir_key)),
+            None => {
+                let (created_dir, _) = try!(dir_helper.create(it.clone(),
+                                                              tag,
+                                                              Vec::new(),
+                                                              is_versioned,
+                                                              access_level.clone(),
+                                                              Some(&mut current_dir_listing)));
+                invalidate_cached_directory(params, &current_dir_listing);
+                created_paths.push(current_path.clone());
+                created_dir
+            }
+        };
+    }
+
+    Ok((current_dir_listing, created_paths))
+}
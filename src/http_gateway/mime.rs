@@ -0,0 +1,75 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+// Content types of the file extensions common on websites, sorted by extension
+const MIME_TYPES: [(&'static str, &'static str); 28] =
+    [("css", "text/css; charset=utf-8"),
+     ("csv", "text/csv; charset=utf-8"),
+     ("gif", "image/gif"),
+     ("htm", "text/html; charset=utf-8"),
+     ("html", "text/html; charset=utf-8"),
+     ("ico", "image/x-icon"),
+     ("jpeg", "image/jpeg"),
+     ("jpg", "image/jpeg"),
+     ("js", "application/javascript"),
+     ("json", "application/json"),
+     ("md", "text/markdown; charset=utf-8"),
+     ("mp3", "audio/mpeg"),
+     ("mp4", "video/mp4"),
+     ("ogg", "audio/ogg"),
+     ("otf", "font/otf"),
+     ("pdf", "application/pdf"),
+     ("png", "image/png"),
+     ("svg", "image/svg+xml"),
+     ("ttf", "font/ttf"),
+     ("txt", "text/plain; charset=utf-8"),
+     ("wasm", "application/wasm"),
+     ("wav", "audio/wav"),
+     ("webm", "video/webm"),
+     ("webp", "image/webp"),
+     ("woff", "font/woff"),
+     ("woff2", "font/woff2"),
+     ("xml", "application/xml"),
+     ("zip", "application/zip")];
+
+/// Guesses the content type of a file from the extension of its name, ignoring case
+pub fn guess(file_name: &str) -> &'static str {
+    let extension = match file_name.rfind('.') {
+        Some(index) => file_name[index + 1..].to_lowercase(),
+        None => return "application/octet-stream",
+    };
+    match MIME_TYPES.binary_search_by(|&(known, _)| known.cmp(&extension[..])) {
+        Ok(index) => MIME_TYPES[index].1,
+        Err(_) => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::MIME_TYPES;
+
+    #[test]
+    fn guess_mime_types() {
+        assert!(MIME_TYPES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(guess("index.html"), "text/html; charset=utf-8");
+        assert_eq!(guess("photo.JPG"), "image/jpeg");
+        assert_eq!(guess("archive.tar.zip"), "application/zip");
+        assert_eq!(guess("README"), "application/octet-stream");
+        assert_eq!(guess("data.unknown"), "application/octet-stream");
+    }
+}
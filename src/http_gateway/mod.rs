@@ -0,0 +1,542 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+//! Local HTTP server previewing the sites published through DNS in an ordinary browser.
+
+mod mime;
+mod request;
+
+use std::io::Write;
+use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use errors::FfiError;
+use ParameterPacket;
+use directory_cache::DirectoryCache;
+use dns::resolve::{Resolved, SafeUrl};
+use nfs::file_reader::FileReader;
+use safe_core::core::client::Client;
+use safe_core::dns::errors::DnsError;
+use safe_core::nfs::errors::NfsError;
+use safe_core::nfs::directory_listing::DirectoryListing;
+use maidsafe_utilities::thread::RaiiThreadJoiner;
+use self::request::{ByteRange, HttpRequest};
+
+// Content is read from the network and written out in pieces of at most this many bytes
+const CHUNK_SIZE: u64 = 1024 * 1024;
+// Time after which a connection which sends nothing is dropped
+const READ_TIMEOUT_SECS: u64 = 10;
+// Time after which a connection which stops reading the response is dropped, so that the
+// gateway thread is not blocked by it
+const WRITE_TIMEOUT_SECS: u64 = 10;
+
+/// HTTP server bound to a local port which serves the content of `safe://` URLs. A request for
+/// `http://service.long_name/path` is answered as `safe://service.long_name/path` would be
+/// resolved. As a browser sends `localhost` or an IP address as the host, the site may instead be
+/// given as the first segment of the path, as in `http://localhost:port/service.long_name/path`.
+/// Requests are served one at a time and the server stops when dropped.
+pub struct HttpGateway {
+    address: SocketAddr,
+    stop_flag: Arc<AtomicBool>,
+    _raii_joiner: RaiiThreadJoiner,
+}
+
+impl HttpGateway {
+    /// Binds to `port` on the loopback interface, or to a free port if `port` is 0, and serves
+    /// requests with `client` from a background thread
+    pub fn start(client: Arc<Mutex<Client>>,
+                 directory_cache: Arc<Mutex<DirectoryCache>>,
+                 port: u16)
+                 -> Result<HttpGateway, FfiError> {
+        let listener = try!(TcpListener::bind(("127.0.0.1", port)).map_err(|error| {
+            FfiError::Unexpected(format!("Failed to bind the HTTP gateway to port {}: {:?}",
+                                         port,
+                                         error))
+        }));
+        let address = try!(listener.local_addr().map_err(|error| {
+            FfiError::Unexpected(format!("Failed to get the address of the HTTP gateway: {:?}",
+                                         error))
+        }));
+
+        let params = ParameterPacket {
+            client: client,
+            app_root_dir_key: None,
+            safe_drive_access: false,
+            safe_drive_dir_key: None,
+            directory_cache: directory_cache,
+        };
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let cloned_stop_flag = stop_flag.clone();
+        let raii_joiner = RaiiThreadJoiner::new(thread!("FfiHttpGateway", move || {
+            for stream in listener.incoming() {
+                if cloned_stop_flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                match stream {
+                    Ok(stream) => serve_connection(&params, stream),
+                    Err(error) => debug!("HTTP gateway failed to accept a connection: {:?}", error),
+                }
+            }
+        }));
+
+        Ok(HttpGateway {
+            address: address,
+            stop_flag: stop_flag,
+            _raii_joiner: raii_joiner,
+        })
+    }
+
+    /// Port the server is bound to
+    pub fn get_port(&self) -> u16 {
+        self.address.port()
+    }
+}
+
+impl Drop for HttpGateway {
+    fn drop(&mut self) {
+        // The listening thread only looks at the flag between connections, so it is woken up with
+        // one of its own before being joined
+        self.stop_flag.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.address);
+    }
+}
+
+struct Response {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Body,
+}
+
+enum Body {
+    Bytes(Vec<u8>),
+    // Offsets of the first and last bytes of the file to send
+    File(FileReader, u64, u64),
+}
+
+impl Response {
+    fn new(status: u16, content_type: &str, body: Vec<u8>) -> Response {
+        Response {
+            status: status,
+            headers: vec![("Content-Type", content_type.to_string())],
+            body: Body::Bytes(body),
+        }
+    }
+
+    fn error(status: u16) -> Response {
+        let message = format!("{} {}\n", status, get_reason(status));
+        Response::new(status, "text/plain; charset=utf-8", message.into_bytes())
+    }
+}
+
+fn serve_connection(params: &ParameterPacket, mut stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(READ_TIMEOUT_SECS)));
+    let _ = stream.set_write_timeout(Some(Duration::from_secs(WRITE_TIMEOUT_SECS)));
+    let (response, is_head) = match HttpRequest::read(&mut stream) {
+        Ok(request) => (get_response(params, &request), request.method == "HEAD"),
+        Err(error) => {
+            debug!("HTTP gateway received a malformed request: {}", error);
+            (Response::error(400), false)
+        }
+    };
+
+    if let Err(error) = write_response(&mut stream, response, is_head) {
+        debug!("HTTP gateway failed to write a response: {:?}", error);
+    }
+}
+
+fn get_response(params: &ParameterPacket, request: &HttpRequest) -> Response {
+    if request.method != "GET" && request.method != "HEAD" {
+        let mut response = Response::error(405);
+        response.headers.push(("Allow", "GET, HEAD".to_string()));
+        return response;
+    }
+
+    let target = request.target.splitn(2, |c| c == '?' || c == '#').next().unwrap_or("");
+    let (url, prefix) = match get_url(request.get_header("Host").unwrap_or(""), target) {
+        Some(url_and_prefix) => url_and_prefix,
+        None => return Response::error(404),
+    };
+    let url = match SafeUrl::parse(&url) {
+        Ok(url) => url,
+        Err(error) => {
+            debug!("HTTP gateway failed to parse {:?}: {:?}", url, error);
+            return Response::error(400);
+        }
+    };
+
+    let resolved = match url.resolve(params) {
+        Ok(resolved) => resolved,
+        Err(error) => {
+            debug!("HTTP gateway failed to resolve {:?}: {:?}", url, error);
+            return Response::error(get_error_status(&error));
+        }
+    };
+
+    // Relative links of a directory's page only work if its URL ends in a slash
+    let requested_path = url.tokens.iter().fold(String::new(), |joined, name| joined + "/" + name);
+    let is_directory = match resolved {
+        Resolved::File(_, ref path) => *path != requested_path,
+        Resolved::Directory(..) => true,
+    };
+    if is_directory && !target.ends_with('/') && (!url.tokens.is_empty() || !prefix.is_empty()) {
+        let mut response = Response::error(301);
+        response.headers.push(("Location", format!("{}/", target)));
+        return response;
+    }
+
+    match resolved {
+        Resolved::File(file, _) => {
+            let content_type = mime::guess(file.get_name());
+            let reader = FileReader::new(params.client.clone(), file);
+            get_file_response(reader, content_type, request.get_header("Range"))
+        }
+        Resolved::Directory(dir, path) => {
+            let base = format!("{}{}", prefix, path.trim_right_matches('/'));
+            Response::new(200,
+                          "text/html; charset=utf-8",
+                          get_directory_page(&dir, &base).into_bytes())
+        }
+    }
+}
+
+// Status answering a request whose URL failed to resolve with `error`: not found if the URL leads
+// nowhere and an internal error if looking it up failed
+fn get_error_status(error: &FfiError) -> u16 {
+    match *error {
+        FfiError::PathNotFound |
+        FfiError::InvalidPath => 404,
+        FfiError::DnsError(ref error) => {
+            match **error {
+                DnsError::DnsRecordNotFound |
+                DnsError::ServiceNotFound => 404,
+                _ => 500,
+            }
+        }
+        FfiError::NfsError(ref error) => {
+            match **error {
+                NfsError::DirectoryNotFound |
+                NfsError::FileNotFound => 404,
+                _ => 500,
+            }
+        }
+        FfiError::WithDetails { ref error, .. } => get_error_status(error),
+        _ => 500,
+    }
+}
+
+// Builds the `safe://` URL asked for by a request for `target` made to `host`, along with the
+// prefix of `target` naming the site when it is given in the path rather than as the host
+fn get_url(host: &str, target: &str) -> Option<(String, String)> {
+    let host = match host.rfind(':') {
+        Some(colon) if !host.ends_with(']') => &host[..colon],
+        _ => host,
+    };
+    let is_local = host.is_empty() || host == "localhost" || host.starts_with('[') ||
+                   host.parse::<::std::net::Ipv4Addr>().is_ok();
+    if !is_local {
+        return Some((format!("safe://{}{}", host, target), String::new()));
+    }
+
+    let path = target.trim_left_matches('/');
+    let end = path.find('/').unwrap_or(path.len());
+    if end == 0 {
+        return None;
+    }
+    Some((format!("safe://{}", path), format!("/{}", &path[..end])))
+}
+
+fn get_file_response(reader: FileReader, content_type: &str, range: Option<&str>) -> Response {
+    let size = reader.size();
+    let mut response = Response::new(200, content_type, Vec::new());
+    response.headers.push(("Accept-Ranges", "bytes".to_string()));
+    match request::parse_range(range, size) {
+        ByteRange::Whole => {
+            if size > 0 {
+                response.body = Body::File(reader, 0, size - 1);
+            }
+        }
+        ByteRange::Partial(first, last) => {
+            response.status = 206;
+            response.headers.push(("Content-Range", format!("bytes {}-{}/{}", first, last, size)));
+            response.body = Body::File(reader, first, last);
+        }
+        ByteRange::Unsatisfiable => {
+            response = Response::error(416);
+            response.headers.push(("Content-Range", format!("bytes */{}", size)));
+        }
+    }
+
+    response
+}
+
+fn get_directory_page(dir: &DirectoryListing, base: &str) -> String {
+    let title = escape_html(&format!("{}/", base));
+    let mut page = format!("<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title>\
+                            </head><body><h1>{}</h1><ul>\n",
+                           title,
+                           title);
+    let names = dir.get_sub_directories()
+                   .iter()
+                   .map(|metadata| format!("{}/", metadata.get_name()))
+                   .chain(dir.get_files().iter().map(|file| file.get_name().clone()));
+    for name in names {
+        page.push_str(&format!("<li><a href=\"{}/{}\">{}</a></li>\n",
+                               escape_html(base),
+                               percent_encode(&name),
+                               escape_html(&name)));
+    }
+    page.push_str("</ul></body></html>\n");
+    page
+}
+
+fn write_response(stream: &mut TcpStream,
+                  response: Response,
+                  is_head: bool)
+                  -> Result<(), FfiError> {
+    let content_length = match response.body {
+        Body::Bytes(ref bytes) => bytes.len() as u64,
+        Body::File(_, first, last) => last - first + 1,
+    };
+    let mut head = format!("HTTP/1.1 {} {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+                           response.status,
+                           get_reason(response.status),
+                           content_length);
+    for &(name, ref value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    try!(write_all(stream, head.as_bytes()));
+    if is_head {
+        return Ok(());
+    }
+
+    match response.body {
+        Body::Bytes(bytes) => write_all(stream, &bytes),
+        Body::File(reader, first, last) => {
+            let mut offset = first;
+            while offset <= last {
+                let length = ::std::cmp::min(CHUNK_SIZE, last - offset + 1);
+                let chunk = try!(reader.read(offset, length));
+                if chunk.is_empty() {
+                    return Err(FfiError::from("File ended before its recorded size"));
+                }
+                try!(write_all(stream, &chunk));
+                offset += chunk.len() as u64;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn write_all(stream: &mut TcpStream, bytes: &[u8]) -> Result<(), FfiError> {
+    stream.write_all(bytes).map_err(|error| {
+        FfiError::Unexpected(format!("Failed to write to the connection: {:?}", error))
+    })
+}
+
+fn get_reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        206 => "Partial Content",
+        301 => "Moved Permanently",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        416 => "Range Not Satisfiable",
+        _ => "Internal Server Error",
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Escapes everything but the unreserved characters of URLs and the trailing slash of directories
+fn percent_encode(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for (index, byte) in name.bytes().enumerate() {
+        match byte {
+            b'A'...b'Z' | b'a'...b'z' | b'0'...b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if index == name.len() - 1 => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::{get_error_status, percent_encode};
+
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    use Action;
+    use test_utils;
+    use dns::register_dns::RegisterDns;
+    use safe_core::core::utility;
+    use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG};
+    use safe_core::nfs::helper::file_helper::FileHelper;
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+    // Sends a request and returns the status, the head and the body of the response
+    fn send(port: u16, request: &str) -> (u16, String, Vec<u8>) {
+        let mut stream = unwrap_result!(TcpStream::connect(("127.0.0.1", port)));
+        unwrap_result!(stream.write_all(request.as_bytes()));
+        let mut response = Vec::new();
+        let _ = unwrap_result!(stream.read_to_end(&mut response));
+
+        let end = unwrap_option!(response.windows(4).position(|window| window == b"\r\n\r\n"),
+                                 "Response has no head");
+        let head = unwrap_result!(String::from_utf8(response[..end].to_vec()));
+        let status = unwrap_result!(head[9..12].parse::<u16>());
+        (status, head, response[end + 4..].to_vec())
+    }
+
+    fn get(port: u16, host: &str, target: &str, extra_headers: &str) -> (u16, String, Vec<u8>) {
+        send(port,
+             &format!("GET {} HTTP/1.1\r\nHost: {}\r\n{}\r\n", target, host, extra_headers))
+    }
+
+    #[test]
+    fn serve_site() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+
+        // Creates /site/index.html and /site/docs/notes.txt
+        let mut app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let (mut site, _) = unwrap_result!(dir_helper.create("site".to_string(),
+                                                             UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                             Vec::new(),
+                                                             false,
+                                                             AccessLevel::Public,
+                                                             Some(&mut app_root_dir)));
+        let (docs, _) = unwrap_result!(dir_helper.create("docs".to_string(),
+                                                         UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                         Vec::new(),
+                                                         false,
+                                                         AccessLevel::Public,
+                                                         Some(&mut site)));
+        let mut writer = unwrap_result!(file_helper.create("notes.txt".to_string(),
+                                                           Vec::new(),
+                                                           docs));
+        writer.write(b"0123456789", 0);
+        let _ = unwrap_result!(writer.close());
+        let site = unwrap_result!(dir_helper.get(site.get_key()));
+        let mut writer = unwrap_result!(file_helper.create("index.html".to_string(),
+                                                           Vec::new(),
+                                                           site));
+        writer.write(b"<p>Hello</p>", 0);
+        let _ = unwrap_result!(writer.close());
+
+        let long_name = unwrap_result!(utility::generate_random_string(10));
+        let mut register_request = RegisterDns {
+            long_name: long_name.clone(),
+            service_name: "www".to_string(),
+            is_path_shared: false,
+            service_home_dir_path: "/site".to_string(),
+            extra_owners: None,
+        };
+        assert!(register_request.execute(parameter_packet.clone()).is_ok());
+
+        let gateway = unwrap_result!(HttpGateway::start(parameter_packet.client.clone(),
+                                                        parameter_packet.directory_cache.clone(),
+                                                        0));
+        let port = gateway.get_port();
+        let host = format!("www.{}", long_name);
+
+        let (status, head, body) = get(port, &host, "/", "");
+        assert_eq!(status, 200);
+        assert!(head.contains("Content-Type: text/html; charset=utf-8"));
+        assert_eq!(body, b"<p>Hello</p>".to_vec());
+
+        let (status, head, body) = get(port, &host, "/docs/notes.txt", "Range: bytes=2-4\r\n");
+        assert_eq!(status, 206);
+        assert!(head.contains("Content-Range: bytes 2-4/10"));
+        assert!(head.contains("Content-Type: text/plain; charset=utf-8"));
+        assert_eq!(body, b"234".to_vec());
+
+        let (status, _, _) = get(port, &host, "/docs/notes.txt", "Range: bytes=10-\r\n");
+        assert_eq!(status, 416);
+
+        // The site given in the path, as a browser pointed at localhost sends it
+        let (status, _, body) = get(port,
+                                    &format!("localhost:{}", port),
+                                    &format!("/{}/docs/notes.txt", long_name),
+                                    "");
+        assert_eq!(status, 200);
+        assert_eq!(body, b"0123456789".to_vec());
+
+        let (status, head, _) = get(port, &host, "/docs", "");
+        assert_eq!(status, 301);
+        assert!(head.contains("Location: /docs/"));
+        let (status, _, body) = get(port, &host, "/docs/", "");
+        assert_eq!(status, 200);
+        let page = unwrap_result!(String::from_utf8(body));
+        assert!(page.contains("<a href=\"/docs/notes.txt\">notes.txt</a>"));
+
+        assert_eq!(get(port, &host, "/missing.html", "").0, 404);
+        assert_eq!(get(port, &format!("blog.{}", long_name), "/", "").0, 404);
+        assert_eq!(send(port, "POST / HTTP/1.1\r\nHost: site\r\n\r\n").0, 405);
+        assert_eq!(send(port, "garbage\r\n\r\n").0, 400);
+
+        let (status, head, body) = send(port,
+                                        &format!("HEAD / HTTP/1.1\r\nHost: {}\r\n\r\n", host));
+        assert_eq!(status, 200);
+        assert!(head.contains("Content-Length: 12"));
+        assert!(body.is_empty());
+
+        drop(gateway);
+        assert!(TcpStream::connect(("127.0.0.1", port)).is_err());
+    }
+
+    #[test]
+    fn error_statuses() {
+        use errors::FfiError;
+        use safe_core::dns::errors::DnsError;
+        use safe_core::nfs::errors::NfsError;
+
+        assert_eq!(get_error_status(&FfiError::PathNotFound), 404);
+        assert_eq!(get_error_status(&FfiError::from(DnsError::DnsRecordNotFound)), 404);
+        assert_eq!(get_error_status(&FfiError::from(DnsError::ServiceNotFound)), 404);
+        assert_eq!(get_error_status(&FfiError::from(NfsError::FileNotFound)), 404);
+        assert_eq!(get_error_status(&FfiError::PathNotFound.with_path("/a".to_string())),
+                   404);
+        assert_eq!(get_error_status(&FfiError::from(DnsError::Unexpected(String::new()))),
+                   500);
+        assert_eq!(get_error_status(&FfiError::from(NfsError::FailedToUpdateDirectory)),
+                   500);
+        assert_eq!(get_error_status(&FfiError::Unexpected(String::new())), 500);
+    }
+
+    #[test]
+    fn encode_names() {
+        assert_eq!(percent_encode("a b&c.txt"), "a%20b%26c.txt");
+        assert_eq!(percent_encode("docs/"), "docs/");
+        assert_eq!(percent_encode("r\u{e9}"), "r%C3%A9");
+    }
+}
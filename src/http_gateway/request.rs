@@ -0,0 +1,174 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use std::io::Read;
+
+// Longest request line and headers accepted, in bytes
+const MAX_HEAD_SIZE: usize = 16 * 1024;
+
+/// Request line and headers of an HTTP request. Any body is ignored, as only `GET` and `HEAD` are
+/// served.
+#[derive(Debug)]
+pub struct HttpRequest {
+    pub method: String,
+    pub target: String,
+    headers: Vec<(String, String)>,
+}
+
+/// Part of a file asked for by the `Range` header of a request
+#[derive(Debug, PartialEq)]
+pub enum ByteRange {
+    /// No usable range was given, so the whole file is sent
+    Whole,
+    /// First and last offsets, both inclusive, of the part to send
+    Partial(u64, u64),
+    /// The range lies outside of the file
+    Unsatisfiable,
+}
+
+impl HttpRequest {
+    /// Reads the request line and headers from `reader`
+    pub fn read<R: Read>(reader: &mut R) -> Result<HttpRequest, String> {
+        let mut head = Vec::new();
+        let mut buffer = [0u8; 1024];
+        while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+            if head.len() > MAX_HEAD_SIZE {
+                return Err("Request head is too large".to_string());
+            }
+            match reader.read(&mut buffer) {
+                Ok(0) => return Err("Connection closed before the end of the request".to_string()),
+                Ok(size) => head.extend_from_slice(&buffer[..size]),
+                Err(error) => return Err(format!("Failed to read the request: {:?}", error)),
+            }
+        }
+
+        let head = try!(String::from_utf8(head).map_err(|_| "Request is not UTF-8".to_string()));
+        HttpRequest::parse(&head)
+    }
+
+    /// Parses the request line and headers in `head`
+    pub fn parse(head: &str) -> Result<HttpRequest, String> {
+        let mut lines = head.split("\r\n");
+        let request_line = lines.next().unwrap_or("");
+        let parts = request_line.split(' ').collect::<Vec<_>>();
+        if parts.len() != 3 || !parts[2].starts_with("HTTP/1.") {
+            return Err(format!("Malformed request line {:?}", request_line));
+        }
+
+        let mut headers = Vec::new();
+        for line in lines.take_while(|line| !line.is_empty()) {
+            let colon = try!(line.find(':').ok_or(format!("Malformed header {:?}", line)));
+            let name = line[..colon].trim().to_lowercase();
+            headers.push((name, line[colon + 1..].trim().to_string()));
+        }
+
+        Ok(HttpRequest {
+            method: parts[0].to_string(),
+            target: parts[1].to_string(),
+            headers: headers,
+        })
+    }
+
+    /// Value of the header called `name`, ignoring case
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers
+            .iter()
+            .find(|header| header.0 == name)
+            .map(|header| &header.1[..])
+    }
+}
+
+/// Interprets a `Range` header for a file of `size` bytes. Only single ranges are honoured; a
+/// header which is malformed or asks for several ranges is ignored, as HTTP allows.
+pub fn parse_range(header: Option<&str>, size: u64) -> ByteRange {
+    let spec = match header {
+        Some(header) if header.starts_with("bytes=") && !header.contains(',') => &header[6..],
+        _ => return ByteRange::Whole,
+    };
+    let dash = match spec.find('-') {
+        Some(dash) => dash,
+        None => return ByteRange::Whole,
+    };
+    let (first, last) = (spec[..dash].trim(), spec[dash + 1..].trim());
+
+    let range = if first.is_empty() {
+        // A suffix range asks for the last bytes of the file
+        match last.parse::<u64>() {
+            Ok(0) => return ByteRange::Unsatisfiable,
+            Ok(length) if size > 0 => (size.saturating_sub(length), size - 1),
+            Ok(_) => return ByteRange::Unsatisfiable,
+            Err(_) => return ByteRange::Whole,
+        }
+    } else {
+        let first = match first.parse::<u64>() {
+            Ok(first) => first,
+            Err(_) => return ByteRange::Whole,
+        };
+        let last = if last.is_empty() {
+            size.saturating_sub(1)
+        } else {
+            match last.parse::<u64>() {
+                Ok(last) if last >= first => ::std::cmp::min(last, size.saturating_sub(1)),
+                _ => return ByteRange::Whole,
+            }
+        };
+        if first >= size {
+            return ByteRange::Unsatisfiable;
+        }
+        (first, last)
+    };
+
+    ByteRange::Partial(range.0, range.1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_requests() {
+        let head = "GET /blog.site/a%20b.html?x=1 HTTP/1.1\r\nHost: localhost:8080\r\n\
+                    RANGE: bytes=0-9\r\n\r\n";
+        let request = unwrap_result!(HttpRequest::read(&mut head.as_bytes()));
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.target, "/blog.site/a%20b.html?x=1");
+        assert_eq!(request.get_header("host"), Some("localhost:8080"));
+        assert_eq!(request.get_header("Range"), Some("bytes=0-9"));
+        assert_eq!(request.get_header("Accept"), None);
+
+        assert!(HttpRequest::parse("GET /\r\n\r\n").is_err());
+        assert!(HttpRequest::parse("GET / HTTP/1.1\r\nNoColon\r\n\r\n").is_err());
+        assert!(HttpRequest::read(&mut "GET / HTTP/1.1\r\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn parse_ranges() {
+        assert_eq!(parse_range(None, 100), ByteRange::Whole);
+        assert_eq!(parse_range(Some("bytes=0-9"), 100), ByteRange::Partial(0, 9));
+        assert_eq!(parse_range(Some("bytes=90-"), 100), ByteRange::Partial(90, 99));
+        assert_eq!(parse_range(Some("bytes=90-200"), 100), ByteRange::Partial(90, 99));
+        assert_eq!(parse_range(Some("bytes=-10"), 100), ByteRange::Partial(90, 99));
+        assert_eq!(parse_range(Some("bytes=-200"), 100), ByteRange::Partial(0, 99));
+        assert_eq!(parse_range(Some("bytes=100-"), 100), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=-0"), 100), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=0-"), 0), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=0-1,5-6"), 100), ByteRange::Whole);
+        assert_eq!(parse_range(Some("bytes=9-0"), 100), ByteRange::Whole);
+        assert_eq!(parse_range(Some("items=0-9"), 100), ByteRange::Whole);
+    }
+}
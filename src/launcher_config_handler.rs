@@ -17,6 +17,8 @@
 
 use errors::FfiError;
 use xor_name::XorName;
+use sodiumoxide::crypto::box_;
+use rustc_serialize::{Decodable, Encodable};
 use std::sync::{Arc, Mutex};
 use safe_core::core::client::Client;
 use sodiumoxide::crypto::hash::sha512;
@@ -27,7 +29,8 @@ use safe_core::nfs::metadata::directory_key::DirectoryKey;
 use safe_core::nfs::helper::directory_helper::DirectoryHelper;
 use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG};
 use maidsafe_utilities::serialisation::{serialise, deserialise};
-use config::{LAUNCHER_GLOBAL_CONFIG_FILE_NAME, LAUNCHER_GLOBAL_DIRECTORY_NAME};
+use config::{LAUNCHER_GLOBAL_CONFIG_FILE_NAME, LAUNCHER_GLOBAL_DIRECTORY_NAME,
+             LAUNCHER_MESSAGING_KEYS_FILE_NAME};
 
 #[derive(RustcEncodable, RustcDecodable, Debug)]
 pub struct LauncherConfiguration {
@@ -35,6 +38,14 @@ pub struct LauncherConfiguration {
     pub app_root_dir_key: DirectoryKey,
 }
 
+/// Messaging keypair generated when `long_name` was registered.
+#[derive(RustcEncodable, RustcDecodable, Clone)]
+pub struct DnsMessagingKeys {
+    pub long_name: String,
+    pub public_key: box_::PublicKey,
+    pub secret_key: box_::SecretKey,
+}
+
 pub struct ConfigHandler {
     client: Arc<Mutex<Client>>,
 }
@@ -105,6 +116,40 @@ impl ConfigHandler {
         dir_name
     }
 
+    /// Stores the messaging keypair of `long_name`, replacing any keypair stored for it before.
+    pub fn upsert_messaging_keys(&self, keys: DnsMessagingKeys) -> Result<(), FfiError> {
+        let (mut all_keys, dir_listing) =
+            try!(self.read_config_file::<DnsMessagingKeys>(LAUNCHER_MESSAGING_KEYS_FILE_NAME));
+        if let Some(pos) = all_keys.iter()
+                                   .position(|existing| existing.long_name == keys.long_name) {
+            all_keys[pos] = keys;
+        } else {
+            all_keys.push(keys);
+        }
+        self.write_config_file(LAUNCHER_MESSAGING_KEYS_FILE_NAME, &all_keys, dir_listing)
+    }
+
+    /// Fetches the messaging keypair stored for `long_name`, if any.
+    pub fn get_messaging_keys(&self,
+                              long_name: &String)
+                              -> Result<Option<DnsMessagingKeys>, FfiError> {
+        let (all_keys, _) =
+            try!(self.read_config_file::<DnsMessagingKeys>(LAUNCHER_MESSAGING_KEYS_FILE_NAME));
+        Ok(all_keys.into_iter().find(|keys| keys.long_name == *long_name))
+    }
+
+    /// Forgets the messaging keypair stored for `long_name`. Nothing is written if there is none.
+    pub fn remove_messaging_keys(&self, long_name: &String) -> Result<(), FfiError> {
+        let (mut all_keys, dir_listing) =
+            try!(self.read_config_file::<DnsMessagingKeys>(LAUNCHER_MESSAGING_KEYS_FILE_NAME));
+        let count = all_keys.len();
+        all_keys.retain(|keys| keys.long_name != *long_name);
+        if all_keys.len() == count {
+            return Ok(());
+        }
+        self.write_config_file(LAUNCHER_MESSAGING_KEYS_FILE_NAME, &all_keys, dir_listing)
+    }
+
     fn upsert_to_launcher_global_config(&self,
                                         config: LauncherConfiguration)
                                         -> Result<(), FfiError> {
@@ -125,52 +170,40 @@ impl ConfigHandler {
             global_configs.push(config);
         }
 
-        let file = unwrap_option!(dir_listing.get_files()
-                                             .iter()
-                                             .find(|file| {
-                                                 file.get_name() == LAUNCHER_GLOBAL_CONFIG_FILE_NAME
-                                             }),
-                                  "Logic Error - Launcher start-up should ensure the file must \
-                                   be present at this stage - Report bug.")
-                       .clone();
-
-        let file_helper = FileHelper::new(self.client.clone());
-        let mut writer = try!(file_helper.update_content(file, Overwrite, dir_listing));
-        writer.write(&try!(serialise(&global_configs)), 0);
-        let _ = try!(writer.close());
-
-        Ok(())
+        self.write_config_file(LAUNCHER_GLOBAL_CONFIG_FILE_NAME, &global_configs, dir_listing)
     }
 
     fn get_launcher_global_config_and_dir
         (&self)
          -> Result<(Vec<LauncherConfiguration>, DirectoryListing), FfiError> {
+        self.read_config_file(LAUNCHER_GLOBAL_CONFIG_FILE_NAME)
+    }
+
+    /// Reads a file of the launcher-reserved directory holding a serialised `Vec`, creating the
+    /// file empty if it does not exist yet.
+    fn read_config_file<T>(&self, file_name: &str) -> Result<(Vec<T>, DirectoryListing), FfiError>
+        where T: Decodable
+    {
         let dir_helper = DirectoryHelper::new(self.client.clone());
         let mut dir_listing = try!(dir_helper.get_configuration_directory_listing(
             LAUNCHER_GLOBAL_DIRECTORY_NAME.to_string()));
 
-        let global_configs = {
+        let entries = {
             let file_helper = FileHelper::new(self.client.clone());
             let file = match dir_listing.get_files()
                                         .iter()
-                                        .find(|file| {
-                                            file.get_name() == LAUNCHER_GLOBAL_CONFIG_FILE_NAME
-                                        })
+                                        .find(|file| file.get_name() == file_name)
                                         .map(|f| f.clone()) {
                 Some(file) => file,
                 None => {
-                    dir_listing =
-                        try!(try!(file_helper.create(LAUNCHER_GLOBAL_CONFIG_FILE_NAME.to_string(),
+                    dir_listing = try!(try!(file_helper.create(file_name.to_string(),
                                                                Vec::new(),
                                                                dir_listing))
-                                 .close())
-                            .0;
+                                           .close())
+                                      .0;
                     unwrap_option!(dir_listing.get_files()
                                               .iter()
-                                              .find(|file| {
-                                                  file.get_name() ==
-                                                  LAUNCHER_GLOBAL_CONFIG_FILE_NAME
-                                              })
+                                              .find(|file| file.get_name() == file_name)
                                               .map(|f| f.clone()),
                                    "Error")
                         .clone()
@@ -187,6 +220,28 @@ impl ConfigHandler {
             }
         };
 
-        Ok((global_configs, dir_listing))
+        Ok((entries, dir_listing))
+    }
+
+    fn write_config_file<T>(&self,
+                            file_name: &str,
+                            entries: &Vec<T>,
+                            dir_listing: DirectoryListing)
+                            -> Result<(), FfiError>
+        where T: Encodable
+    {
+        let file = unwrap_option!(dir_listing.get_files()
+                                             .iter()
+                                             .find(|file| file.get_name() == file_name),
+                                  "Logic Error - The file must have been read, and so created, \
+                                   before being written - Report bug.")
+                       .clone();
+
+        let file_helper = FileHelper::new(self.client.clone());
+        let mut writer = try!(file_helper.update_content(file, Overwrite, dir_listing));
+        writer.write(&try!(serialise(entries)), 0);
+        let _ = try!(writer.close());
+
+        Ok(())
     }
 }
@@ -31,7 +31,10 @@
 //! engine is allocated and managed by the crate. This is necessary because it serves as a context
 //! to all operations provided by the crate. Hence the user will obtain the engine on calling any
 //! one of the functions to create it and must preserve it for all subsequent operations. When
-//! done, to release the resources, `drop_client` may be called.
+//! done, to release the resources, `drop_client` may be called. The same holds for the file reader
+//! and writer handles obtained via `open_file_reader` and `open_file_writer`, which must be
+//! released with `close_file_reader` and with `commit_file_writer` or `abort_file_writer`
+//! respectively.
 //!
 //! [Project github page](https://github.com/maidsafe/safe_ffi)
 
@@ -63,21 +66,25 @@
 #[macro_use]
 extern crate log;
 extern crate libc;
+extern crate routing;
 extern crate xor_name;
 extern crate safe_core;
 extern crate sodiumoxide;
 extern crate rustc_serialize;
 #[macro_use]
 extern crate maidsafe_utilities;
+#[cfg(test)]
+extern crate syn;
 
-use errors::FfiError;
+use errors::{ErrorDetails, FfiError};
 use rustc_serialize::json;
 use std::sync::{Arc, Mutex, mpsc};
 use rustc_serialize::Decoder;
 use safe_core::core::client::Client;
 use rustc_serialize::Decodable;
-use libc::{c_void, int32_t, c_char};
+use libc::{c_void, int32_t, uint16_t, uint64_t, c_char};
 use std::mem;
+use std::time::Duration;
 use rustc_serialize::base64::FromBase64;
 use maidsafe_utilities::serialisation::{serialise, deserialise};
 use maidsafe_utilities::thread::RaiiThreadJoiner;
@@ -86,15 +93,26 @@ use safe_core::nfs::metadata::directory_key::DirectoryKey;
 use safe_core::core::translated_events::NetworkEvent;
 use safe_core::core::errors::CoreError;
 use std::sync::mpsc::Sender;
+use worker_pool::{CompletionCallback, WorkerPool};
+use directory_cache::DirectoryCache;
+use http_gateway::HttpGateway;
 
 #[macro_use]mod macros;
 
 mod dns;
 mod nfs;
+mod meta;
+mod path;
+mod batch;
 mod config;
 mod helper;
 mod test_utils;
+mod http_gateway;
+mod worker_pool;
+mod directory_cache;
 mod launcher_config_handler;
+#[cfg(test)]
+mod c_header;
 /// Errors thrown by the FFI operations
 pub mod errors;
 
@@ -110,6 +128,8 @@ pub struct ParameterPacket {
     pub safe_drive_access: bool,
     /// SAFEDrive root directory key
     pub safe_drive_dir_key: Option<DirectoryKey>,
+    /// Cache of directory listings shared by the requests made with the same client handle
+    pub directory_cache: Arc<Mutex<DirectoryCache>>,
 }
 
 impl Clone for ParameterPacket {
@@ -129,6 +149,7 @@ impl Clone for ParameterPacket {
             app_root_dir_key: app_root_dir_key,
             safe_drive_access: self.safe_drive_access,
             safe_drive_dir_key: safe_drive_dir_key,
+            directory_cache: self.directory_cache.clone(),
         }
     }
 }
@@ -140,17 +161,35 @@ pub type ResponseType = Result<Option<String>, ::errors::FfiError>;
 pub trait Action {
     /// ICommand executer
     fn execute(&mut self, params: ParameterPacket) -> ResponseType;
+
+    /// Path the action operates on, attached by the dispatcher to the error the action fails with.
+    /// Actions operating on several paths return `None` and attach the path at fault themselves.
+    fn get_path(&self) -> Option<String> {
+        None
+    }
 }
 
+/// Invoked with the code of each network event, such as Connected or Disconnected, after
+/// registration via `register_network_event_observer`.
+pub type NetworkEventObserver = extern "C" fn(int32_t);
+
 struct FfiHandle {
     client: Arc<Mutex<Client>>,
     network_thread_terminator: Option<Sender<NetworkEvent>>,
     raii_joiner: Option<RaiiThreadJoiner>,
-    network_event_observers: Arc<Mutex<Vec<extern "C" fn(i32)>>>,
+    network_event_observers: Arc<Mutex<Vec<NetworkEventObserver>>>,
+    worker_pool: Mutex<Option<WorkerPool>>,
+    last_error: Mutex<Option<ErrorDetails>>,
+    directory_cache: Arc<Mutex<DirectoryCache>>,
+    http_gateway: Mutex<Option<HttpGateway>>,
 }
 
 impl Drop for FfiHandle {
     fn drop(&mut self) {
+        // Workers and the HTTP gateway hold on to the client, so they are shut down before
+        // anything else
+        *unwrap_result!(self.worker_pool.lock()) = None;
+        *unwrap_result!(self.http_gateway.lock()) = None;
         if let Some(ref network_thread_terminator) = self.network_thread_terminator {
             let _ = network_thread_terminator.send(NetworkEvent::Terminated);
         }
@@ -160,9 +199,11 @@ impl Drop for FfiHandle {
 /// This function should be called to enable logging to a file
 #[no_mangle]
 pub extern "C" fn init_logging() -> int32_t {
-    ffi_try!(safe_log::init(false).map_err(CoreError::Unexpected));
+    ffi_try!(helper::catch_unwind(|| {
+        ffi_try!(safe_log::init(false).map_err(CoreError::Unexpected));
 
-    0
+        0
+    }))
 }
 
 /// Create an unregistered client. This or any one of the other companion functions to get a
@@ -170,11 +211,13 @@ pub extern "C" fn init_logging() -> int32_t {
 #[no_mangle]
 #[allow(unsafe_code)]
 pub extern "C" fn create_unregistered_client(ffi_handle: *mut *const c_void) -> int32_t {
-    unsafe {
-        *ffi_handle = cast_to_ffi_handle(ffi_try!(Client::create_unregistered_client()));
-    }
+    ffi_try!(helper::catch_unwind(|| {
+        unsafe {
+            *ffi_handle = cast_to_ffi_handle(ffi_try!(Client::create_unregistered_client()));
+        }
 
-    0
+        0
+    }))
 }
 
 /// Create a registered client. This or any one of the other companion functions to get a
@@ -188,14 +231,17 @@ pub extern "C" fn create_account(c_keyword: *const c_char,
                                  c_password: *const c_char,
                                  ffi_handle: *mut *const c_void)
                                  -> int32_t {
-    let client = ffi_try!(Client::create_account(ffi_try!(helper::c_char_ptr_to_string(c_keyword)),
-                                        ffi_try!(helper::c_char_ptr_to_string(c_pin)),
-                                        ffi_try!(helper::c_char_ptr_to_string(c_password))));
-    unsafe {
-        *ffi_handle = cast_to_ffi_handle(client);
-    }
+    ffi_try!(helper::catch_unwind(|| {
+        let keyword = ffi_try!(helper::c_char_ptr_to_string(c_keyword));
+        let pin = ffi_try!(helper::c_char_ptr_to_string(c_pin));
+        let password = ffi_try!(helper::c_char_ptr_to_string(c_password));
+        let client = ffi_try!(Client::create_account(keyword, pin, password));
+        unsafe {
+            *ffi_handle = cast_to_ffi_handle(client);
+        }
 
-    0
+        0
+    }))
 }
 
 /// Log into a registered client. This or any one of the other companion functions to get a
@@ -209,14 +255,16 @@ pub extern "C" fn log_in(c_keyword: *const c_char,
                          c_password: *const c_char,
                          ffi_handle: *mut *const c_void)
                          -> int32_t {
-    let client = ffi_try!(Client::log_in(ffi_try!(helper::c_char_ptr_to_string(c_keyword)),
-                                         ffi_try!(helper::c_char_ptr_to_string(c_pin)),
-                                         ffi_try!(helper::c_char_ptr_to_string(c_password))));
-    unsafe {
-        *ffi_handle = cast_to_ffi_handle(client);
-    }
+    ffi_try!(helper::catch_unwind(|| {
+        let client = ffi_try!(Client::log_in(ffi_try!(helper::c_char_ptr_to_string(c_keyword)),
+                                             ffi_try!(helper::c_char_ptr_to_string(c_pin)),
+                                             ffi_try!(helper::c_char_ptr_to_string(c_password))));
+        unsafe {
+            *ffi_handle = cast_to_ffi_handle(client);
+        }
 
-    0
+        0
+    }))
 }
 
 /// Register an observer to network events like Connected, Disconnected etc. as provided by the
@@ -224,33 +272,40 @@ pub extern "C" fn log_in(c_keyword: *const c_char,
 #[no_mangle]
 #[allow(unsafe_code)]
 pub extern "C" fn register_network_event_observer(handle: *const c_void,
-                                                  callback: extern "C" fn(i32)) {
-    let mut ffi_handle: Box<FfiHandle> = unsafe { mem::transmute(handle) };
-
-    unwrap_result!(ffi_handle.network_event_observers.lock()).push(callback);
-
-    if ffi_handle.raii_joiner.is_none() {
-        let callbacks = ffi_handle.network_event_observers.clone();
-
-        let (tx, rx) = mpsc::channel();
-        let cloned_tx = tx.clone();
-        unwrap_result!(ffi_handle.client.lock()).add_network_event_observer(tx);
-
-        let raii_joiner = RaiiThreadJoiner::new(thread!("FfiNetworkEventObserver", move || {
-            for it in rx.iter() {
-                let ref cbs = *unwrap_result!(callbacks.lock());
-                let event_ffi_val = it.into();
-                for cb in cbs {
-                    cb(event_ffi_val);
+                                                  callback: NetworkEventObserver) {
+    let _ = helper::catch_unwind(|| {
+        let ffi_handle = unsafe { &mut *(handle as *mut FfiHandle) };
+
+        unwrap_result!(ffi_handle.network_event_observers.lock()).push(callback);
+
+        if ffi_handle.raii_joiner.is_none() {
+            let callbacks = ffi_handle.network_event_observers.clone();
+
+            let (tx, rx) = mpsc::channel();
+            let cloned_tx = tx.clone();
+            unwrap_result!(ffi_handle.client.lock()).add_network_event_observer(tx);
+
+            let raii_joiner = RaiiThreadJoiner::new(thread!("FfiNetworkEventObserver", move || {
+                for it in rx.iter() {
+                    let is_terminated = match it {
+                        NetworkEvent::Terminated => true,
+                        _ => false,
+                    };
+                    let ref cbs = *unwrap_result!(callbacks.lock());
+                    let event_ffi_val = it.into();
+                    for cb in cbs {
+                        cb(event_ffi_val);
+                    }
+                    if is_terminated {
+                        break;
+                    }
                 }
-            }
-        }));
+            }));
 
-        ffi_handle.raii_joiner = Some(raii_joiner);
-        ffi_handle.network_thread_terminator = Some(cloned_tx);
-    }
-
-    mem::forget(ffi_handle);
+            ffi_handle.raii_joiner = Some(raii_joiner);
+            ffi_handle.network_thread_terminator = Some(cloned_tx);
+        }
+    });
 }
 
 /// Returns key size
@@ -264,25 +319,37 @@ pub extern "C" fn get_app_dir_key(c_app_name: *const c_char,
                                   c_result: *mut int32_t,
                                   ffi_handle: *const c_void)
                                   -> *const u8 {
-    let client = cast_from_ffi_handle(ffi_handle);
-    let app_name: String = ffi_ptr_try!(helper::c_char_ptr_to_string(c_app_name), c_result);
-    let app_id: String = ffi_ptr_try!(helper::c_char_ptr_to_string(c_app_id), c_result);
-    let vendor: String = ffi_ptr_try!(helper::c_char_ptr_to_string(c_vendor), c_result);
-    let handler = launcher_config_handler::ConfigHandler::new(client);
-    let dir_key = ffi_ptr_try!(handler.get_app_dir_key(app_name, app_id, vendor), c_result);
-    let mut serialised_data = ffi_ptr_try!(serialise(&dir_key).map_err(|e| FfiError::from(e)),
-                                           c_result);
-    serialised_data.shrink_to_fit();
-    unsafe {
-        std::ptr::write(c_size, serialised_data.len() as i32);
-        std::ptr::write(c_capacity, serialised_data.capacity() as i32);
-        std::ptr::write(c_result, 0);
-    }
+    ffi_ptr_try!(helper::catch_unwind(|| {
+        let client = cast_from_ffi_handle(ffi_handle);
+        let app_name: String = ffi_handle_ptr_try!(helper::c_char_ptr_to_string(c_app_name),
+                                                   c_result,
+                                                   ffi_handle);
+        let app_id: String = ffi_handle_ptr_try!(helper::c_char_ptr_to_string(c_app_id),
+                                                 c_result,
+                                                 ffi_handle);
+        let vendor: String = ffi_handle_ptr_try!(helper::c_char_ptr_to_string(c_vendor),
+                                                 c_result,
+                                                 ffi_handle);
+        let handler = launcher_config_handler::ConfigHandler::new(client);
+        let dir_key = ffi_handle_ptr_try!(handler.get_app_dir_key(app_name, app_id, vendor),
+                                          c_result,
+                                          ffi_handle);
+        let mut serialised_data = ffi_handle_ptr_try!(serialise(&dir_key)
+                                                          .map_err(|e| FfiError::from(e)),
+                                                      c_result,
+                                                      ffi_handle);
+        serialised_data.shrink_to_fit();
+        unsafe {
+            std::ptr::write(c_size, serialised_data.len() as i32);
+            std::ptr::write(c_capacity, serialised_data.capacity() as i32);
+            std::ptr::write(c_result, 0);
+        }
 
-    let ptr = serialised_data.as_ptr();
-    mem::forget(serialised_data);
+        let ptr = serialised_data.as_ptr();
+        mem::forget(serialised_data);
 
-    ptr
+        ptr
+    }), c_result)
 }
 
 /// Returns Key as base64 string
@@ -293,30 +360,52 @@ pub extern "C" fn get_safe_drive_key(c_size: *mut int32_t,
                                      c_result: *mut int32_t,
                                      ffi_handle: *const c_void)
                                      -> *const u8 {
-    let client = cast_from_ffi_handle(ffi_handle);
-    let dir_key = ffi_ptr_try!(helper::get_safe_drive_key(client), c_result);
-    let mut serialised_data = ffi_ptr_try!(serialise(&dir_key).map_err(|e| FfiError::from(e)),
-                                           c_result);
-    serialised_data.shrink_to_fit();
-    unsafe {
-        std::ptr::write(c_size, serialised_data.len() as i32);
-        std::ptr::write(c_capacity, serialised_data.capacity() as i32);
-        std::ptr::write(c_result, 0);
-    }
-    let ptr = serialised_data.as_ptr();
-    mem::forget(serialised_data);
+    ffi_ptr_try!(helper::catch_unwind(|| {
+        let client = cast_from_ffi_handle(ffi_handle);
+        let dir_key = ffi_handle_ptr_try!(helper::get_safe_drive_key(client),
+                                          c_result,
+                                          ffi_handle);
+        let mut serialised_data = ffi_handle_ptr_try!(serialise(&dir_key)
+                                                          .map_err(|e| FfiError::from(e)),
+                                                      c_result,
+                                                      ffi_handle);
+        serialised_data.shrink_to_fit();
+        unsafe {
+            std::ptr::write(c_size, serialised_data.len() as i32);
+            std::ptr::write(c_capacity, serialised_data.capacity() as i32);
+            std::ptr::write(c_result, 0);
+        }
+        let ptr = serialised_data.as_ptr();
+        mem::forget(serialised_data);
 
-    ptr
+        ptr
+    }), c_result)
 }
 
 /// Discard and clean up the previously allocated client. Use this only if the client is obtained
 /// from one of the client obtainment functions in this crate (`crate_account`, `log_in`,
 /// `create_unregistered_client`). Using `client_handle` after a call to this functions is
-/// undefined behaviour.
+/// undefined behaviour. Dropping the client waits for the callbacks of its asynchronous requests
+/// to return, so when called from within one of them the client is not dropped and the error
+/// code for a call from a callback is returned instead.
 #[no_mangle]
 #[allow(unsafe_code)]
-pub extern "C" fn drop_client(client_handle: *const c_void) {
-    let _ = unsafe { mem::transmute::<_, Box<Arc<Mutex<Client>>>>(client_handle) };
+pub extern "C" fn drop_client(client_handle: *const c_void) -> int32_t {
+    ffi_try!(helper::catch_unwind(|| {
+        let is_worker_thread = {
+            let ffi_handle = unsafe { &*(client_handle as *const FfiHandle) };
+            unwrap_result!(ffi_handle.worker_pool.lock())
+                .as_ref()
+                .map_or(false, |worker_pool| worker_pool.is_worker_thread())
+        };
+        if is_worker_thread {
+            return set_last_error(client_handle, FfiError::CalledFromCallback);
+        }
+
+        let _ = unsafe { mem::transmute::<_, Box<FfiHandle>>(client_handle) };
+
+        0
+    }))
 }
 
 /// General function that can be invoked for performing a API specific operation that will return
@@ -327,16 +416,15 @@ pub extern "C" fn drop_client(client_handle: *const c_void) {
 /// safe_drive_access and data. `data` refers to API specific payload.
 #[no_mangle]
 pub extern "C" fn execute(c_payload: *const c_char, ffi_handle: *const c_void) -> int32_t {
-    let payload: String = ffi_try!(helper::c_char_ptr_to_string(c_payload));
-    let json_request = ffi_try!(parse_result!(json::Json::from_str(&payload), "JSON parse error"));
-    let mut json_decoder = json::Decoder::new(json_request);
-    let client = cast_from_ffi_handle(ffi_handle);
-    let (module, action, parameter_packet) = ffi_try!(get_parameter_packet(client,
-                                                                           &mut json_decoder));
-    let result = module_parser(module, action, parameter_packet, &mut json_decoder);
-    let _ = ffi_try!(result);
-
-    0
+    ffi_try!(helper::catch_unwind(|| {
+        let payload: String = ffi_handle_try!(helper::c_char_ptr_to_string(c_payload),
+                                              ffi_handle);
+        let client = cast_from_ffi_handle(ffi_handle);
+        let directory_cache = get_directory_cache(ffi_handle);
+        let _ = ffi_handle_try!(execute_payload(client, directory_cache, &payload), ffi_handle);
+
+        0
+    }))
 }
 
 /// General function that can be invoked for getting data as a resut for an operation.
@@ -351,51 +439,473 @@ pub extern "C" fn execute_for_content(c_payload: *const c_char,
                                       c_result: *mut int32_t,
                                       ffi_handle: *const c_void)
                                       -> *const u8 {
-    let payload: String = ffi_ptr_try!(helper::c_char_ptr_to_string(c_payload), c_result);
-    let json_request = ffi_ptr_try!(parse_result!(json::Json::from_str(&payload),
-                                                  "JSON parse error"),
-                                    c_result);
-    let mut json_decoder = json::Decoder::new(json_request.clone());
-    let client = cast_from_ffi_handle(ffi_handle);
-    let (module, action, parameter_packet) = ffi_ptr_try!(get_parameter_packet(client,
-                                                                               &mut json_decoder),
-                                                          c_result);
-    // TODO Krishna: Avoid parsing it twice (line 292). for get_parameter_packet pass the json
-    // object and iterate. parse based on keys
-    json_decoder = json::Decoder::new(json_request.clone());
-    let result = ffi_ptr_try!(module_parser(module, action, parameter_packet, &mut json_decoder),
-                              c_result);
-    let data = match result {
-        Some(response) => response.into_bytes(),
-        None => Vec::with_capacity(0),
-    };
+    ffi_ptr_try!(helper::catch_unwind(|| {
+        let payload: String = ffi_handle_ptr_try!(helper::c_char_ptr_to_string(c_payload),
+                                                  c_result,
+                                                  ffi_handle);
+        let client = cast_from_ffi_handle(ffi_handle);
+        let directory_cache = get_directory_cache(ffi_handle);
+        let result = ffi_handle_ptr_try!(execute_payload(client, directory_cache, &payload),
+                                         c_result,
+                                         ffi_handle);
+        let data = match result {
+            Some(response) => response.into_bytes(),
+            None => Vec::with_capacity(0),
+        };
 
-    unsafe {
-        std::ptr::write(c_size, data.len() as i32);
-        std::ptr::write(c_capacity, data.capacity() as i32);
-        std::ptr::write(c_result, 0);
-    };
-    let ptr = data.as_ptr();
-    mem::forget(data);
+        unsafe {
+            std::ptr::write(c_size, data.len() as i32);
+            std::ptr::write(c_capacity, data.capacity() as i32);
+            std::ptr::write(c_result, 0);
+        };
+        let ptr = data.as_ptr();
+        mem::forget(data);
 
-    ptr
+        ptr
+    }), c_result)
 }
 
+/// Asynchronous variant of `execute` and `execute_for_content`. c_payload is the same JSON payload
+/// these functions take. The request is queued to a pool of worker threads sharing the client and
+/// this function returns immediately, writing the id of the request to `c_request_id`. On
+/// completion `callback` is invoked from one of the worker threads with `user_data`, the result
+/// code and the response bytes along with their size. The response bytes are only valid for the
+/// duration of the callback. `drop_client` fails if called from within the callback.
 #[no_mangle]
 #[allow(unsafe_code)]
-/// Drop the vector returned as a result of the execute_for_content fn
-pub fn drop_vector(ptr: *mut u8, size: int32_t, capacity: int32_t) {
-    let _ = unsafe { Vec::from_raw_parts(ptr, size as usize, capacity as usize) };
+pub extern "C" fn execute_async(c_payload: *const c_char,
+                                user_data: *mut c_void,
+                                callback: CompletionCallback,
+                                c_request_id: *mut uint64_t,
+                                ffi_handle: *const c_void)
+                                -> int32_t {
+    ffi_try!(helper::catch_unwind(|| {
+        let payload: String = ffi_handle_try!(helper::c_char_ptr_to_string(c_payload),
+                                              ffi_handle);
+
+        let result = {
+            let handle = unsafe { &*(ffi_handle as *const FfiHandle) };
+            let mut worker_pool = unwrap_result!(handle.worker_pool.lock());
+            if worker_pool.is_none() {
+                *worker_pool = Some(WorkerPool::new(handle.client.clone(),
+                                                    handle.directory_cache.clone(),
+                                                    config::ASYNC_WORKER_THREAD_COUNT));
+            }
+            unwrap_option!(worker_pool.as_ref(), "Logic Error - Report bug.")
+                .submit(payload, user_data, callback)
+        };
+
+        let request_id = ffi_handle_try!(result, ffi_handle);
+        unsafe {
+            std::ptr::write(c_request_id, request_id);
+        }
+
+        0
+    }))
+}
+
+/// Cancel a request submitted via `execute_async`. Only a request which is still queued can be
+/// cancelled; its callback is then invoked with the error code for a cancelled request. A request
+/// which is already being executed runs to completion.
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn cancel_async_request(request_id: uint64_t, ffi_handle: *const c_void) -> int32_t {
+    ffi_try!(helper::catch_unwind(|| {
+        let handle = unsafe { &*(ffi_handle as *const FfiHandle) };
+        let result = match *unwrap_result!(handle.worker_pool.lock()) {
+            Some(ref worker_pool) => worker_pool.cancel(request_id),
+            None => Err(FfiError::InvalidRequestId),
+        };
+        ffi_handle_try!(result, ffi_handle);
+
+        0
+    }))
+}
+
+/// Configure the cache of the directory listings fetched while resolving the paths of requests
+/// made with `ffi_handle`. At most `capacity` listings are cached, each for at most
+/// `time_to_live_secs` seconds, after which it is fetched from the network again. Changes made
+/// through this crate invalidate the listings they affect, but changes made by other clients are
+/// only seen once the cached listings expire. The cache is disabled by default and a `capacity` of
+/// 0 disables it again.
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn configure_directory_cache(capacity: uint64_t,
+                                            time_to_live_secs: uint64_t,
+                                            ffi_handle: *const c_void)
+                                            -> int32_t {
+    ffi_try!(helper::catch_unwind(|| {
+        let handle = unsafe { &*(ffi_handle as *const FfiHandle) };
+        unwrap_result!(handle.directory_cache.lock())
+            .configure(capacity as usize, Duration::from_secs(time_to_live_secs));
+
+        0
+    }))
 }
 
+/// Returns the configuration and usage of the directory cache of `ffi_handle` as a JSON string
+/// with the keys capacity, time_to_live_secs, size, hits and misses. The vector is returned as for
+/// execute_for_content and must be released with drop_vector.
 #[no_mangle]
 #[allow(unsafe_code)]
+pub extern "C" fn get_directory_cache_stats(c_size: *mut int32_t,
+                                            c_capacity: *mut int32_t,
+                                            c_result: *mut int32_t,
+                                            ffi_handle: *const c_void)
+                                            -> *const u8 {
+    ffi_ptr_try!(helper::catch_unwind(|| {
+        let handle = unsafe { &*(ffi_handle as *const FfiHandle) };
+        let stats = unwrap_result!(handle.directory_cache.lock()).get_stats();
+        let data = ffi_ptr_try!(json::encode(&stats).map_err(FfiError::from), c_result)
+                       .into_bytes();
+
+        unsafe {
+            std::ptr::write(c_size, data.len() as i32);
+            std::ptr::write(c_capacity, data.capacity() as i32);
+            std::ptr::write(c_result, 0);
+        };
+        let ptr = data.as_ptr();
+        mem::forget(data);
+
+        ptr
+    }), c_result)
+}
+
+/// Start a local HTTP server previewing the sites published through DNS, using the client of
+/// `ffi_handle`. It binds to `port` on the loopback interface, or to a free port if `port` is 0,
+/// and writes the port it bound to to `c_port`. `http://service.long_name/path` is served as
+/// `safe://service.long_name/path` resolves with the `resolve` action of the `dns` module, and
+/// the site may instead be given as the first segment of the path, as in
+/// `http://localhost:port/service.long_name/path`. Only one server runs per handle.
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn start_http_gateway(port: uint16_t,
+                                     c_port: *mut uint16_t,
+                                     ffi_handle: *const c_void)
+                                     -> int32_t {
+    ffi_try!(helper::catch_unwind(|| {
+        let handle = unsafe { &*(ffi_handle as *const FfiHandle) };
+        let mut http_gateway = unwrap_result!(handle.http_gateway.lock());
+        let result = if http_gateway.is_some() {
+            Err(FfiError::from("HTTP gateway is already running"))
+        } else {
+            HttpGateway::start(handle.client.clone(), handle.directory_cache.clone(), port)
+        };
+
+        let started_gateway = ffi_handle_try!(result, ffi_handle);
+        unsafe {
+            std::ptr::write(c_port, started_gateway.get_port());
+        }
+        *http_gateway = Some(started_gateway);
+
+        0
+    }))
+}
+
+/// Stop the HTTP server started with `start_http_gateway`, if any. The server is also stopped by
+/// `drop_client`.
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn stop_http_gateway(ffi_handle: *const c_void) -> int32_t {
+    ffi_try!(helper::catch_unwind(|| {
+        let handle = unsafe { &*(ffi_handle as *const FfiHandle) };
+        *unwrap_result!(handle.http_gateway.lock()) = None;
+
+        0
+    }))
+}
+
+/// Drop the vector returned as a result of the execute_for_content fn
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn drop_vector(ptr: *mut u8, size: int32_t, capacity: int32_t) {
+    let _ = helper::catch_unwind(|| {
+        let _ = unsafe { Vec::from_raw_parts(ptr, size as usize, capacity as usize) };
+    });
+}
+
 /// Drop the null pointer returned as error from the execute_for_content fn
-pub fn drop_null_ptr(ptr: *mut u8) {
-    let _ = unsafe { libc::free(ptr as *mut c_void) };
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn drop_null_ptr(ptr: *mut u8) {
+    let _ = helper::catch_unwind(|| {
+        let _ = unsafe { libc::free(ptr as *mut c_void) };
+    });
+}
+
+/// Open a reader over the content of a file. c_payload is a JSON string with the keys
+/// app_dir_key, safe_drive_dir_key, safe_drive_access and data, where `data` holds file_path and
+/// is_path_shared exactly as for the nfs get-file action. The file content is never copied into
+/// JSON; it is read piece by piece via `read_from_file_reader`. The reader must be released with
+/// `close_file_reader`.
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn open_file_reader(c_payload: *const c_char,
+                                   ffi_handle: *const c_void,
+                                   reader_handle: *mut *const c_void)
+                                   -> int32_t {
+    ffi_try!(helper::catch_unwind(|| {
+        let payload: String = ffi_handle_try!(helper::c_char_ptr_to_string(c_payload),
+                                              ffi_handle);
+        let json_request = ffi_handle_try!(parse_result!(json::Json::from_str(&payload),
+                                                         "JSON parse error"),
+                                           ffi_handle);
+        let mut json_decoder = json::Decoder::new(json_request.clone());
+        let client = cast_from_ffi_handle(ffi_handle);
+        let directory_cache = get_directory_cache(ffi_handle);
+        let parameter_packet = ffi_handle_try!(parse_parameter_packet(client,
+                                                                      directory_cache,
+                                                                      &mut json_decoder),
+                                               ffi_handle);
+        let request: nfs::file_reader::OpenFileReader =
+            ffi_handle_try!(helper::decode_data(&json_request, &[]), ffi_handle);
+        let reader = ffi_handle_try!(request.open(parameter_packet), ffi_handle);
+        unsafe {
+            *reader_handle = mem::transmute(Box::new(reader));
+        }
+
+        0
+    }))
+}
+
+/// Returns the size of the file opened by `open_file_reader`
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn get_file_reader_size(reader_handle: *const c_void) -> uint64_t {
+    helper::catch_unwind(|| {
+        let reader = unsafe { &*(reader_handle as *const nfs::file_reader::FileReader) };
+        reader.size()
+    }).unwrap_or(0)
+}
+
+/// Read at most `length` bytes starting at `offset` into `c_buffer`, which must be allocated by
+/// the caller to hold at least `length` bytes. The number of bytes actually read, which is less
+/// than `length` only at the end of the file, is written to `c_read_size`.
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn read_from_file_reader(reader_handle: *const c_void,
+                                        offset: uint64_t,
+                                        length: uint64_t,
+                                        c_buffer: *mut u8,
+                                        c_read_size: *mut uint64_t)
+                                        -> int32_t {
+    ffi_try!(helper::catch_unwind(|| {
+        let reader = unsafe { &*(reader_handle as *const nfs::file_reader::FileReader) };
+        let data = ffi_try!(reader.read(offset, length));
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), c_buffer, data.len());
+            std::ptr::write(c_read_size, data.len() as uint64_t);
+        }
+
+        0
+    }))
+}
+
+/// Discard a reader obtained from `open_file_reader`. Using `reader_handle` after a call to this
+/// function is undefined behaviour.
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn close_file_reader(reader_handle: *const c_void) {
+    let _ = helper::catch_unwind(|| {
+        let _ = unsafe { mem::transmute::<_, Box<nfs::file_reader::FileReader>>(reader_handle) };
+    });
+}
+
+/// Open a writer over the content of an existing file. c_payload is a JSON string with the keys
+/// app_dir_key, safe_drive_dir_key, safe_drive_access and data, where `data` holds file_path,
+/// is_path_shared and mode. `mode` is either "overwrite" to replace the content or "modify" to
+/// change it in place. Raw bytes are then sent via `write_to_file_writer`. The directory listing
+/// of the file is updated only when the writer is committed with `commit_file_writer`;
+/// `abort_file_writer` discards everything written.
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn open_file_writer(c_payload: *const c_char,
+                                   ffi_handle: *const c_void,
+                                   writer_handle: *mut *const c_void)
+                                   -> int32_t {
+    ffi_try!(helper::catch_unwind(|| {
+        let payload: String = ffi_handle_try!(helper::c_char_ptr_to_string(c_payload),
+                                              ffi_handle);
+        let json_request = ffi_handle_try!(parse_result!(json::Json::from_str(&payload),
+                                                         "JSON parse error"),
+                                           ffi_handle);
+        let mut json_decoder = json::Decoder::new(json_request.clone());
+        let client = cast_from_ffi_handle(ffi_handle);
+        let directory_cache = get_directory_cache(ffi_handle);
+        let parameter_packet = ffi_handle_try!(parse_parameter_packet(client,
+                                                                      directory_cache,
+                                                                      &mut json_decoder),
+                                               ffi_handle);
+        let request: nfs::file_writer::OpenFileWriter =
+            ffi_handle_try!(helper::decode_data(&json_request, &[]), ffi_handle);
+        let writer = ffi_handle_try!(request.open(parameter_packet), ffi_handle);
+        unsafe {
+            *writer_handle = mem::transmute(Box::new(writer));
+        }
+
+        0
+    }))
+}
+
+/// Write `length` bytes from `c_data` at `offset` of the file opened by `open_file_writer`
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn write_to_file_writer(writer_handle: *const c_void,
+                                       c_data: *const u8,
+                                       length: uint64_t,
+                                       offset: uint64_t) {
+    let _ = helper::catch_unwind(|| {
+        let writer = unsafe { &mut *(writer_handle as *mut nfs::file_writer::FileWriter) };
+        let data = unsafe { std::slice::from_raw_parts(c_data, length as usize) };
+        writer.write(data, offset);
+    });
+}
+
+/// Commit everything written via the writer and update the directory listing of the file. The
+/// writer is released whether or not the commit succeeds, so using `writer_handle` after a call to
+/// this function is undefined behaviour.
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn commit_file_writer(writer_handle: *const c_void) -> int32_t {
+    ffi_try!(helper::catch_unwind(|| {
+        let writer = unsafe {
+            mem::transmute::<_, Box<nfs::file_writer::FileWriter>>(writer_handle)
+        };
+        ffi_try!(writer.commit());
+
+        0
+    }))
+}
+
+/// Discard a writer obtained from `open_file_writer` along with everything written through it.
+/// Using `writer_handle` after a call to this function is undefined behaviour.
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn abort_file_writer(writer_handle: *const c_void) {
+    let _ = helper::catch_unwind(|| {
+        let _ = unsafe { mem::transmute::<_, Box<nfs::file_writer::FileWriter>>(writer_handle) };
+    });
+}
+
+/// Returns the details of the error of the last failed call made with `ffi_handle` as a JSON string
+/// with the keys category, code, description, field and path. `field` is the JSON field missing
+/// from or malformed in the request and `path` the path the request operated on; either is null
+/// when not known. Calls which succeed leave the details untouched, so this should be called right
+/// after the call which failed. Errors of asynchronous requests are only reported to their
+/// callbacks. The vector, which is empty if no call has failed yet, is returned as for
+/// execute_for_content and must be released with drop_vector.
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn get_last_error(c_size: *mut int32_t,
+                                 c_capacity: *mut int32_t,
+                                 c_result: *mut int32_t,
+                                 ffi_handle: *const c_void)
+                                 -> *const u8 {
+    ffi_ptr_try!(helper::catch_unwind(|| {
+        let handle = unsafe { &*(ffi_handle as *const FfiHandle) };
+        let encoded = match *unwrap_result!(handle.last_error.lock()) {
+            Some(ref details) => json::encode(details).map(|details| details.into_bytes()),
+            None => Ok(Vec::with_capacity(0)),
+        };
+        let data = ffi_ptr_try!(encoded.map_err(FfiError::from), c_result);
+
+        unsafe {
+            std::ptr::write(c_size, data.len() as i32);
+            std::ptr::write(c_capacity, data.capacity() as i32);
+            std::ptr::write(c_result, 0);
+        };
+        let ptr = data.as_ptr();
+        mem::forget(data);
+
+        ptr
+    }), c_result)
+}
+
+/// Returns the name, owning module and description of `code`, which may be any code returned by
+/// this crate, as a JSON string with the keys code, name, module and description. The vector is
+/// returned as for execute_for_content and must be released with drop_vector.
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn describe_error_code(code: int32_t,
+                                      c_size: *mut int32_t,
+                                      c_capacity: *mut int32_t,
+                                      c_result: *mut int32_t)
+                                      -> *const u8 {
+    ffi_ptr_try!(helper::catch_unwind(|| {
+        let unknown_code = FfiError::SpecificParseError(format!("{} is not an error code of \
+                                                                 this crate",
+                                                                code));
+        let info = ffi_ptr_try!(errors::describe_error_code(code).ok_or(unknown_code), c_result);
+        let data = ffi_ptr_try!(json::encode(&info).map_err(FfiError::from), c_result).into_bytes();
+
+        unsafe {
+            std::ptr::write(c_size, data.len() as i32);
+            std::ptr::write(c_capacity, data.capacity() as i32);
+            std::ptr::write(c_result, 0);
+        };
+        let ptr = data.as_ptr();
+        mem::forget(data);
+
+        ptr
+    }), c_result)
+}
+
+/// Returns the table of all the error codes of this crate, as a JSON array of objects with the
+/// keys code, name, module and description, for bindings to generate their error types from.
+/// Codes of safe_core which are not listed individually can still be looked up with
+/// `describe_error_code`. The vector is returned as for execute_for_content and must be released
+/// with drop_vector.
+#[no_mangle]
+#[allow(unsafe_code)]
+pub extern "C" fn get_error_codes(c_size: *mut int32_t,
+                                  c_capacity: *mut int32_t,
+                                  c_result: *mut int32_t)
+                                  -> *const u8 {
+    ffi_ptr_try!(helper::catch_unwind(|| {
+        let data = ffi_ptr_try!(json::encode(&errors::get_error_codes()).map_err(FfiError::from),
+                                c_result)
+                       .into_bytes();
+
+        unsafe {
+            std::ptr::write(c_size, data.len() as i32);
+            std::ptr::write(c_capacity, data.capacity() as i32);
+            std::ptr::write(c_result, 0);
+        };
+        let ptr = data.as_ptr();
+        mem::forget(data);
+
+        ptr
+    }), c_result)
+}
+
+// Keeps the details of `error` on the handle for `get_last_error` and returns its numeric code
+#[allow(unsafe_code)]
+fn set_last_error(ffi_handle: *const c_void, error: FfiError) -> int32_t {
+    let details = ErrorDetails::from(error);
+    let code = details.code;
+
+    let handle = unsafe { &*(ffi_handle as *const FfiHandle) };
+    *unwrap_result!(handle.last_error.lock()) = Some(details);
+
+    code
+}
+
+fn execute_payload(client: Arc<Mutex<Client>>,
+                   directory_cache: Arc<Mutex<DirectoryCache>>,
+                   payload: &str)
+                   -> ResponseType {
+    let json_request = try!(parse_result!(json::Json::from_str(payload), "JSON parse error"));
+    let mut json_decoder = json::Decoder::new(json_request.clone());
+    let (module, action, parameter_packet) = try!(get_parameter_packet(client,
+                                                                       directory_cache,
+                                                                       &mut json_decoder));
+    // Also reached from the worker threads of `execute_async`, which have no FFI boundary of their
+    // own to catch a panic at
+    try!(helper::catch_unwind(|| module_parser(module, action, parameter_packet, json_request)))
 }
 
 fn get_parameter_packet<D>(client: Arc<Mutex<Client>>,
+                           directory_cache: Arc<Mutex<DirectoryCache>>,
                            json_decoder: &mut D)
                            -> Result<(String, String, ParameterPacket), ::errors::FfiError>
     where D: Decoder,
@@ -410,6 +920,18 @@ fn get_parameter_packet<D>(client: Arc<Mutex<Client>>,
                                                 Decodable::decode(d)
                                             }),
                                             ""));
+    let parameter_packet = try!(parse_parameter_packet(client, directory_cache, json_decoder));
+
+    Ok((module, action, parameter_packet))
+}
+
+fn parse_parameter_packet<D>(client: Arc<Mutex<Client>>,
+                             directory_cache: Arc<Mutex<DirectoryCache>>,
+                             json_decoder: &mut D)
+                             -> Result<ParameterPacket, ::errors::FfiError>
+    where D: Decoder,
+          D::Error: ::std::fmt::Debug
+{
     let base64_safe_drive_dir_key: Option<String> =
         json_decoder.read_struct_field("safe_drive_dir_key", 2, |d| Decodable::decode(d))
                     .ok();
@@ -448,28 +970,28 @@ fn get_parameter_packet<D>(client: Arc<Mutex<Client>>,
         None
     };
 
-    Ok((module,
-        action,
-        ParameterPacket {
+    Ok(ParameterPacket {
         client: client,
         app_root_dir_key: app_root_dir_key,
         safe_drive_access: safe_drive_access,
         safe_drive_dir_key: safe_drive_dir_key,
-    }))
+        directory_cache: directory_cache,
+    })
 }
 
-fn module_parser<D>(module: String,
-                    action: String,
-                    parameter_packet: ParameterPacket,
-                    decoder: &mut D)
-                    -> ResponseType
-    where D: Decoder,
-          D::Error: ::std::fmt::Debug
-{
+fn module_parser(module: String,
+                 action: String,
+                 parameter_packet: ParameterPacket,
+                 request: json::Json)
+                 -> ResponseType {
     match &module[..] {
-        "dns" => dns::action_dispatcher(action, parameter_packet, decoder),
-        "nfs" => nfs::action_dispatcher(action, parameter_packet, decoder),
-        _ => unimplemented!(),
+        "batch" => batch::action_dispatcher(action, parameter_packet, &request),
+        "dns" => dns::action_dispatcher(action, parameter_packet, &request),
+        "meta" => meta::action_dispatcher(action, parameter_packet, &request),
+        "nfs" => nfs::action_dispatcher(action, parameter_packet, &request),
+        _ => {
+            Err(FfiError::SpecificParseError(format!("Unsupported module {:?}.", module)))
+        }
     }
 }
 
@@ -480,6 +1002,10 @@ fn cast_to_ffi_handle(client: Client) -> *const c_void {
         network_thread_terminator: None,
         raii_joiner: None,
         network_event_observers: Arc::new(Mutex::new(Vec::with_capacity(3))),
+        worker_pool: Mutex::new(None),
+        last_error: Mutex::new(None),
+        directory_cache: Arc::new(Mutex::new(DirectoryCache::default())),
+        http_gateway: Mutex::new(None),
     });
 
     unsafe { mem::transmute(ffi_handle) }
@@ -487,12 +1013,14 @@ fn cast_to_ffi_handle(client: Client) -> *const c_void {
 
 #[allow(unsafe_code)]
 fn cast_from_ffi_handle(handle: *const c_void) -> Arc<Mutex<Client>> {
-    let ffi_handle: Box<FfiHandle> = unsafe { mem::transmute(handle) };
-
-    let client = ffi_handle.client.clone();
-    mem::forget(ffi_handle);
+    let ffi_handle = unsafe { &*(handle as *const FfiHandle) };
+    ffi_handle.client.clone()
+}
 
-    client
+#[allow(unsafe_code)]
+fn get_directory_cache(handle: *const c_void) -> Arc<Mutex<DirectoryCache>> {
+    let ffi_handle = unsafe { &*(handle as *const FfiHandle) };
+    ffi_handle.directory_cache.clone()
 }
 
 #[cfg(test)]
@@ -509,6 +1037,7 @@ mod test {
     use std::time::Duration;
 
     use libc::c_void;
+    use rustc_serialize::json;
 
     fn generate_random_cstring(len: usize) -> Result<::std::ffi::CString, ::errors::FfiError> {
         let mut cstring_vec = try!(::safe_core::core::utility::generate_random_vector::<u8>(len));
@@ -580,6 +1109,125 @@ mod test {
         }
     }
 
+    #[test]
+    fn unknown_module_and_panics() {
+        let parameter_packet = unwrap_result!(::test_utils::get_unregistered_parameter_packet());
+
+        let payload = "{\"module\": \"nsf\", \"action\": \"get-dir\", \"data\": {}}";
+        match ::execute_payload(parameter_packet.client.clone(),
+                                parameter_packet.directory_cache.clone(),
+                                payload) {
+            Err(::errors::FfiError::SpecificParseError(_)) => (),
+            result => panic!("Unexpected result {:?}", result),
+        }
+
+        match ::helper::catch_unwind(|| -> i32 { panic!("Test panic") }) {
+            Err(::errors::FfiError::Unexpected(message)) => assert!(message.contains("Test panic")),
+            result => panic!("Unexpected result {:?}", result),
+        }
+        assert_eq!(unwrap_result!(::helper::catch_unwind(|| 1)), 1);
+    }
+
+    extern "C" fn drop_client_callback(user_data: *mut c_void,
+                                       _: int32_t,
+                                       _: *const u8,
+                                       _: int32_t) {
+        let &(ffi_handle, ref tx) = unsafe {
+            &*(user_data as *const (*const c_void, Mutex<mpsc::Sender<i32>>))
+        };
+        unwrap_result!(unwrap_result!(tx.lock()).send(drop_client(ffi_handle)));
+    }
+
+    #[test]
+    fn drop_client_from_callback() {
+        let mut ffi_handle = 0 as *const c_void;
+        assert_eq!(create_unregistered_client(&mut ffi_handle), 0);
+
+        let (tx, rx) = mpsc::channel();
+        let user_data = (ffi_handle, Mutex::new(tx));
+        let user_data_ptr: *const (*const c_void, Mutex<mpsc::Sender<i32>>) = &user_data;
+        let payload = unwrap_result!(::std::ffi::CString::new("{}"));
+        let mut request_id = 0;
+        assert_eq!(execute_async(payload.as_ptr(),
+                                 user_data_ptr as *mut c_void,
+                                 drop_client_callback,
+                                 &mut request_id,
+                                 ffi_handle),
+                   0);
+
+        let expected: i32 = FfiError::CalledFromCallback.into();
+        assert_eq!(unwrap_result!(rx.recv()), expected);
+        assert_eq!(drop_client(ffi_handle), 0);
+    }
+
+    fn get_last_error_details(ffi_handle: *const c_void) -> json::Json {
+        let mut size = 0;
+        let mut capacity = 0;
+        let mut result = 0;
+        let ptr = get_last_error(&mut size, &mut capacity, &mut result, ffi_handle);
+        assert_eq!(result, 0);
+
+        let data = unsafe { ::std::slice::from_raw_parts(ptr, size as usize) }.to_vec();
+        drop_vector(ptr as *mut u8, size, capacity);
+        unwrap_result!(json::Json::from_str(&unwrap_result!(String::from_utf8(data))))
+    }
+
+    #[test]
+    fn last_error_details() {
+        let mut ffi_handle = 0 as *const c_void;
+        assert_eq!(create_unregistered_client(&mut ffi_handle), 0);
+
+        let mut size = 0;
+        let mut capacity = 0;
+        let mut result = 0;
+        let ptr = get_last_error(&mut size, &mut capacity, &mut result, ffi_handle);
+        assert_eq!(result, 0);
+        assert_eq!(size, 0);
+        drop_vector(ptr as *mut u8, size, capacity);
+
+        let payload = unwrap_result!(::std::ffi::CString::new("{\"module\": \"nfs\", \"action\": \
+                                                               \"get-dir\", \"data\": \
+                                                               {\"is_path_shared\": false}}"));
+        let code = execute(payload.as_ptr(), ffi_handle);
+        assert!(code != 0);
+        let details = get_last_error_details(ffi_handle);
+        assert_eq!(details.find("code").and_then(|code| code.as_i64()),
+                   Some(code as i64));
+        assert_eq!(details.find("category").and_then(|category| category.as_string()),
+                   Some("SpecificParseError"));
+        assert_eq!(details.find("field").and_then(|field| field.as_string()),
+                   Some("dir_path"));
+
+        let payload = unwrap_result!(::std::ffi::CString::new("{\"module\": \"nfs\", \"action\": \
+                                                               \"get-dir\", \"data\": \
+                                                               {\"dir_path\": 5, \
+                                                               \"is_path_shared\": false}}"));
+        let code = execute(payload.as_ptr(), ffi_handle);
+        assert!(code != 0);
+        let details = get_last_error_details(ffi_handle);
+        assert_eq!(details.find("field").and_then(|field| field.as_string()),
+                   Some("dir_path"));
+        assert!(unwrap_option!(details.find("path"), "").is_null());
+
+        // No application directory key is given
+        let payload = unwrap_result!(::std::ffi::CString::new("{\"module\": \"nfs\", \"action\": \
+                                                               \"get-dir\", \"data\": \
+                                                               {\"dir_path\": \"/a/b\", \
+                                                               \"is_path_shared\": false}}"));
+        let code = execute(payload.as_ptr(), ffi_handle);
+        assert!(code != 0);
+        let details = get_last_error_details(ffi_handle);
+        assert_eq!(details.find("code").and_then(|code| code.as_i64()),
+                   Some(code as i64));
+        assert_eq!(details.find("description").and_then(|description| description.as_string()),
+                   Some("Application directory key is not present"));
+        assert_eq!(details.find("path").and_then(|path| path.as_string()),
+                   Some("/a/b"));
+        assert!(unwrap_option!(details.find("field"), "").is_null());
+
+        drop_client(ffi_handle);
+    }
+
     // Enable this test when doing explicit file-logging
     #[test]
     #[ignore]
@@ -608,4 +1256,72 @@ mod test {
         assert!(file_content.contains(&debug_msg[..]));
         assert!(!file_content.contains(&junk_msg[..]));
     }
+
+    #[test]
+    fn directory_cache_invalidation() {
+        use rustc_serialize::base64::ToBase64;
+        use maidsafe_utilities::serialisation::serialise;
+        use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG};
+        use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+        let parameter_packet = unwrap_result!(::test_utils::get_parameter_packet(false));
+        unwrap_result!(parameter_packet.directory_cache.lock())
+            .configure(16, Duration::from_secs(60));
+        let app_dir_key = unwrap_option!(parameter_packet.app_root_dir_key.clone(), "");
+        let app_dir_key = unwrap_result!(serialise(&app_dir_key))
+                              .to_base64(::config::get_base64_config());
+        let execute = |action: &str, data: &str| {
+            let payload = format!("{{\"module\": \"nfs\", \"action\": \"{}\", \"app_dir_key\": \
+                                   \"{}\", \"data\": {}}}",
+                                  action,
+                                  app_dir_key,
+                                  data);
+            unwrap_result!(::execute_payload(parameter_packet.client.clone(),
+                                             parameter_packet.directory_cache.clone(),
+                                             &payload))
+        };
+        let create_dir_data = |path: &str| {
+            format!("{{\"dir_path\": \"{}\", \"is_private\": true, \"is_versioned\": false, \
+                     \"user_metadata\": \"\", \"is_path_shared\": false}}",
+                    path)
+        };
+        let get_dir_data = "{\"dir_path\": \"/cached_dir\", \"is_path_shared\": false}";
+
+        let _ = execute("create-dir", &create_dir_data("/cached_dir"));
+        let _ = execute("get-dir", get_dir_data);
+        let _ = execute("get-dir", get_dir_data);
+        let hits = unwrap_result!(parameter_packet.directory_cache.lock()).get_stats().hits;
+        assert!(hits > 0);
+
+        // The cached listing of the parent is dropped once a directory is created in it
+        let _ = execute("create-dir", &create_dir_data("/cached_dir/sub_dir"));
+        let response = unwrap_option!(execute("get-dir", get_dir_data), "");
+        let response = unwrap_result!(json::Json::from_str(&response));
+        let sub_directories = unwrap_option!(response.find("sub_directories")
+                                                     .and_then(|sub_dirs| sub_dirs.as_array()),
+                                             "");
+        assert_eq!(sub_directories.len(), 1);
+
+        // A directory created by another client, bypassing the cache, is kept when a mutation
+        // updates the directory listing which is still cached
+        let directory_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let app_dir_key = unwrap_option!(parameter_packet.app_root_dir_key.clone(), "");
+        let app_dir = unwrap_result!(directory_helper.get(&app_dir_key));
+        let cached_dir_key = {
+            let metadata = unwrap_option!(app_dir.find_sub_directory(&"cached_dir".to_string()),
+                                          "");
+            metadata.get_key().clone()
+        };
+        let mut cached_dir = unwrap_result!(directory_helper.get(&cached_dir_key));
+        let _ = unwrap_result!(directory_helper.create("other_client_dir".to_string(),
+                                                       UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                       Vec::new(),
+                                                       false,
+                                                       AccessLevel::Private,
+                                                       Some(&mut cached_dir)));
+        let _ = execute("create-dir", &create_dir_data("/cached_dir/own_dir"));
+        let cached_dir = unwrap_result!(directory_helper.get(&cached_dir_key));
+        assert!(cached_dir.find_sub_directory(&"other_client_dir".to_string()).is_some());
+        assert!(cached_dir.find_sub_directory(&"own_dir".to_string()).is_some());
+    }
 }
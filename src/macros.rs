@@ -42,6 +42,33 @@ macro_rules! ffi_ptr_try {
     }
 }
 
+macro_rules! ffi_handle_try {
+    ($result:expr, $ffi_handle:expr) => {
+        match $result {
+            Ok(value)  => value,
+            Err(error) => {
+                let decorator = ::std::iter::repeat('-').take(50).collect::<String>();
+                error!("\n\n {}\n| {:?}\n {}\n\n", decorator, error, decorator);
+                return ::set_last_error($ffi_handle, error)
+            },
+        }
+    }
+}
+
+macro_rules! ffi_handle_ptr_try {
+    ($result:expr, $out:expr, $ffi_handle:expr) => {
+        match $result {
+            Ok(value)  => value,
+            Err(error) => {
+                let decorator = ::std::iter::repeat('-').take(50).collect::<String>();
+                error!("\n\n {}\n| {:?}\n {}\n\n", decorator, error, decorator);
+                let code = ::set_last_error($ffi_handle, error);
+                unsafe { ::std::ptr::write($out, code) };
+                return ::std::ptr::null();
+            },
+        }
+    }
+}
 
 /// This macro is intended to be used in all cases where we get an Err out of Result<T, U> and want
 /// to package it into `safe_ffi::errors::FfiError::SpecificParseError(String)`. This is
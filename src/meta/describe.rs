@@ -0,0 +1,101 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use std::collections::BTreeMap;
+
+use {batch, dns, meta, nfs};
+use rustc_serialize::json::{self, Json, ToJson};
+use {ParameterPacket, ResponseType, Action};
+
+/// Lists every module with its actions and the fields of their `data`, along with the version of
+/// this crate, for clients to detect the features available and validate requests before sending
+/// them.
+#[derive(RustcDecodable, Debug)]
+pub struct Describe;
+
+impl Action for Describe {
+    fn execute(&mut self, _: ParameterPacket) -> ResponseType {
+        let modules = vec![("batch", batch::get_action_schemas()),
+                           ("dns", dns::get_action_schemas()),
+                           ("meta", meta::get_action_schemas()),
+                           ("nfs", nfs::get_action_schemas())];
+
+        let modules = modules.into_iter()
+                             .map(|(name, actions)| {
+                                 let mut module = BTreeMap::new();
+                                 let _ = module.insert("name".to_string(), name.to_json());
+                                 let _ = module.insert("actions".to_string(), actions.to_json());
+                                 Json::Object(module)
+                             })
+                             .collect::<Vec<_>>();
+
+        let mut response = BTreeMap::new();
+        let _ = response.insert("version".to_string(), env!("CARGO_PKG_VERSION").to_json());
+        let _ = response.insert("modules".to_string(), Json::Array(modules));
+
+        Ok(Some(try!(json::encode(&Json::Object(response)))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {Action, test_utils};
+    use rustc_serialize::json::Json;
+
+    #[test]
+    fn describe() {
+        let parameter_packet = unwrap_result!(test_utils::get_unregistered_parameter_packet());
+
+        let response = unwrap_option!(unwrap_result!(Describe.execute(parameter_packet)), "");
+        let json = unwrap_result!(Json::from_str(&response));
+        assert_eq!(json.find("version").and_then(|version| version.as_string()),
+                   Some(env!("CARGO_PKG_VERSION")));
+
+        let modules = unwrap_option!(json.find("modules").and_then(|modules| modules.as_array()),
+                                     "");
+        let nfs = unwrap_option!(modules.iter().find(|module| {
+                                     module.find("name").and_then(|name| name.as_string()) ==
+                                     Some("nfs")
+                                 }),
+                                 "");
+        let get_dir = unwrap_option!(unwrap_option!(nfs.find("actions")
+                                                       .and_then(|actions| actions.as_array()),
+                                                    "")
+                                         .iter()
+                                         .find(|action| {
+                                             action.find("name")
+                                                   .and_then(|name| name.as_string()) ==
+                                             Some("get-dir")
+                                         }),
+                                     "");
+        let version = unwrap_option!(get_dir.find_path(&["fields"])
+                                            .and_then(|fields| fields.as_array())
+                                            .and_then(|fields| {
+                                                fields.iter().find(|field| {
+                                                    field.find("name")
+                                                         .and_then(|name| name.as_string()) ==
+                                                    Some("version")
+                                                })
+                                            }),
+                                     "");
+        assert_eq!(version.find("type").and_then(|field_type| field_type.as_string()),
+                   Some("integer"));
+        assert_eq!(version.find("required").and_then(|required| required.as_boolean()),
+                   Some(false));
+    }
+}
@@ -0,0 +1,169 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use std::collections::BTreeMap;
+
+use rustc_serialize::json::{Json, ToJson};
+
+/// Type of a field in the `data` of a request
+pub enum FieldType {
+    String,
+    Bool,
+    Integer,
+    /// Nested object with the given fields
+    Object(Vec<FieldSchema>),
+    /// Array of objects with the given fields
+    Array(Vec<FieldSchema>),
+}
+
+/// Describes a field in the `data` of a request
+pub struct FieldSchema {
+    pub name: &'static str,
+    pub field_type: FieldType,
+    pub is_required: bool,
+}
+
+/// Describes an action of a module along with the fields of its `data`
+pub struct ActionSchema {
+    pub name: &'static str,
+    pub fields: Vec<FieldSchema>,
+}
+
+impl ActionSchema {
+    pub fn new(name: &'static str, fields: Vec<FieldSchema>) -> ActionSchema {
+        ActionSchema {
+            name: name,
+            fields: fields,
+        }
+    }
+}
+
+pub fn required(name: &'static str, field_type: FieldType) -> FieldSchema {
+    FieldSchema {
+        name: name,
+        field_type: field_type,
+        is_required: true,
+    }
+}
+
+pub fn optional(name: &'static str, field_type: FieldType) -> FieldSchema {
+    FieldSchema {
+        name: name,
+        field_type: field_type,
+        is_required: false,
+    }
+}
+
+impl ToJson for FieldSchema {
+    fn to_json(&self) -> Json {
+        let mut tree = BTreeMap::new();
+        let _ = tree.insert("name".to_string(), self.name.to_json());
+        let _ = tree.insert("required".to_string(), self.is_required.to_json());
+        let field_type = match self.field_type {
+            FieldType::String => "string",
+            FieldType::Bool => "bool",
+            FieldType::Integer => "integer",
+            FieldType::Object(ref fields) => {
+                let _ = tree.insert("fields".to_string(), fields.to_json());
+                "object"
+            }
+            FieldType::Array(ref fields) => {
+                let _ = tree.insert("fields".to_string(), fields.to_json());
+                "array"
+            }
+        };
+        let _ = tree.insert("type".to_string(), field_type.to_json());
+
+        Json::Object(tree)
+    }
+}
+
+impl ToJson for ActionSchema {
+    fn to_json(&self) -> Json {
+        let mut tree = BTreeMap::new();
+        let _ = tree.insert("name".to_string(), self.name.to_json());
+        let _ = tree.insert("fields".to_string(), self.fields.to_json());
+
+        Json::Object(tree)
+    }
+}
+
+/// Name of the first of `fields` which `data` does not match, being either missing while required
+/// or of another type. The names of nested fields are prefixed with the names of their parents,
+/// as in `new_values.content.bytes`.
+pub fn find_mismatched_field(fields: &[FieldSchema], data: &Json) -> Option<String> {
+    for field in fields {
+        let value = match data.find(field.name) {
+            Some(&Json::Null) | None if field.is_required => return Some(field.name.to_string()),
+            Some(&Json::Null) | None => continue,
+            Some(value) => value,
+        };
+        let mismatched_child = match (&field.field_type, value) {
+            (&FieldType::String, &Json::String(_)) |
+            (&FieldType::Bool, &Json::Boolean(_)) |
+            (&FieldType::Integer, &Json::I64(_)) |
+            (&FieldType::Integer, &Json::U64(_)) => None,
+            (&FieldType::Object(ref children), &Json::Object(_)) => {
+                find_mismatched_field(children, value)
+            }
+            (&FieldType::Array(ref children), &Json::Array(ref items)) => {
+                items.iter().filter_map(|item| find_mismatched_field(children, item)).next()
+            }
+            _ => return Some(field.name.to_string()),
+        };
+        if let Some(child) = mismatched_child {
+            return Some(format!("{}.{}", field.name, child));
+        }
+    }
+
+    None
+}
+
+/// Builds a request holding `data` made of placeholder values of the described types, for tests
+/// to check the schemas against the decoding of the actions. Optional fields are included only if
+/// `include_optional` is set and the top level field named `omitted`, if any, is left out.
+#[cfg(test)]
+pub fn get_sample_request(fields: &[FieldSchema],
+                          include_optional: bool,
+                          omitted: Option<&str>)
+                          -> Json {
+    fn get_sample_data(fields: &[FieldSchema],
+                       include_optional: bool,
+                       omitted: Option<&str>)
+                       -> Json {
+        let mut tree = BTreeMap::new();
+        for field in fields.iter()
+                           .filter(|field| field.is_required || include_optional)
+                           .filter(|field| Some(field.name) != omitted) {
+            let value = match field.field_type {
+                FieldType::String => Json::String(String::new()),
+                FieldType::Bool => Json::Boolean(false),
+                FieldType::Integer => Json::U64(0),
+                FieldType::Object(ref fields) => get_sample_data(fields, include_optional, None),
+                FieldType::Array(_) => Json::Array(Vec::new()),
+            };
+            let _ = tree.insert(field.name.to_string(), value);
+        }
+
+        Json::Object(tree)
+    }
+
+    let mut request = BTreeMap::new();
+    let _ = request.insert("data".to_string(),
+                           get_sample_data(fields, include_optional, omitted));
+    Json::Object(request)
+}
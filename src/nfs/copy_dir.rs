@@ -0,0 +1,194 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use errors::FfiError;
+use {helper, path, ParameterPacket, ResponseType, Action};
+use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+use safe_core::nfs::directory_listing::DirectoryListing;
+use safe_core::nfs::errors::NfsError::DirectoryAlreadyExistsWithSameName;
+
+#[derive(RustcDecodable, Debug)]
+pub struct CopyDirectory {
+    src_path: String,
+    is_src_path_shared: bool,
+    dest_path: String,
+    is_dest_path_shared: bool,
+}
+
+impl Action for CopyDirectory {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        if (self.is_src_path_shared || self.is_dest_path_shared) && !params.safe_drive_access {
+            return Err(FfiError::PermissionDenied);
+        }
+
+        // Copying a directory into its own subtree would never terminate
+        if self.is_src_path_shared == self.is_dest_path_shared {
+            let src_tokens = try!(path::tokenise(&self.src_path));
+            let dest_tokens = try!(path::tokenise(&self.dest_path));
+            if dest_tokens.starts_with(&src_tokens) {
+                return Err(FfiError::InvalidPath);
+            }
+        }
+
+        let directory_helper = DirectoryHelper::new(params.client.clone());
+        let src_dir = try!(helper::get_directory(&params,
+                                                 self.is_src_path_shared,
+                                                 &self.src_path)
+                           .map_err(|error| error.with_path(self.src_path.clone())));
+        let mut dest_dir = try!(helper::get_directory_to_modify(&params,
+                                                                self.is_dest_path_shared,
+                                                                &self.dest_path)
+                                .map_err(|error| error.with_path(self.dest_path.clone())));
+        if dest_dir.find_sub_directory(src_dir.get_metadata().get_name()).is_some() {
+            return Err(FfiError::from(DirectoryAlreadyExistsWithSameName));
+        }
+
+        try!(copy_directory(&directory_helper, &src_dir, &mut dest_dir));
+        helper::invalidate_cached_directory(&params, &dest_dir);
+
+        Ok(None)
+    }
+}
+
+// Creates a new listing for `src_dir` and, recursively, for each of its descendants, so that the
+// copy shares no directory listing with the source. Timestamps are set last since adding entries
+// to a listing updates its modification time.
+fn copy_directory(directory_helper: &DirectoryHelper,
+                  src_dir: &DirectoryListing,
+                  dest_parent_dir: &mut DirectoryListing)
+                  -> Result<(), FfiError> {
+    let src_metadata = src_dir.get_metadata();
+    let (mut dir, _) = try!(directory_helper.create(src_metadata.get_name().clone(),
+                                                    src_metadata.get_key().get_type_tag(),
+                                                    src_metadata.get_user_metadata().clone(),
+                                                    src_metadata.get_key().is_versioned(),
+                                                    src_metadata.get_access_level().clone(),
+                                                    Some(&mut *dest_parent_dir)));
+
+    for file in src_dir.get_files() {
+        dir.get_mut_files().push(file.clone());
+    }
+
+    for sub_dir_metadata in src_dir.get_sub_directories() {
+        let sub_dir = try!(directory_helper.get(sub_dir_metadata.get_key()));
+        try!(copy_directory(directory_helper, &sub_dir, &mut dir));
+    }
+
+    dir.get_mut_metadata().set_created_time(src_metadata.get_created_time().clone());
+    dir.get_mut_metadata().set_modified_time(src_metadata.get_modified_time().clone());
+    let _ = try!(directory_helper.update(&dir));
+    dest_parent_dir.upsert_sub_directory(dir.get_metadata().clone());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {Action, ParameterPacket, test_utils};
+    use safe_core::nfs::helper::file_helper::FileHelper;
+    use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG};
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+    fn create_test_tree(parameter_packet: &ParameterPacket) {
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let mut app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let (mut src_dir, _) = unwrap_result!(dir_helper.create("src_dir".to_string(),
+                                                                UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                                vec![1u8; 4],
+                                                                false,
+                                                                AccessLevel::Private,
+                                                                Some(&mut app_root_dir)));
+        let _ = unwrap_result!(dir_helper.create("dest_dir".to_string(),
+                                                 UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                 Vec::new(),
+                                                 false,
+                                                 AccessLevel::Private,
+                                                 Some(&mut app_root_dir)));
+        let (sub_dir, _) = unwrap_result!(dir_helper.create("sub_dir".to_string(),
+                                                            UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                            Vec::new(),
+                                                            false,
+                                                            AccessLevel::Private,
+                                                            Some(&mut src_dir)));
+        let writer = unwrap_result!(file_helper.create("test_file.txt".to_string(),
+                                                       Vec::new(),
+                                                       sub_dir));
+        let _ = unwrap_result!(writer.close());
+    }
+
+    #[test]
+    fn copy_dir() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+
+        create_test_tree(&parameter_packet);
+
+        let mut request = CopyDirectory {
+            src_path: "/src_dir".to_string(),
+            is_src_path_shared: false,
+            dest_path: "/src_dir/sub_dir".to_string(),
+            is_dest_path_shared: false,
+        };
+        assert!(request.execute(parameter_packet.clone()).is_err());
+
+        request.dest_path = "/dest_dir".to_string();
+        assert!(request.execute(parameter_packet.clone()).is_ok());
+        assert!(request.execute(parameter_packet.clone()).is_err());
+
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let src_dir_metadata = unwrap_option!(app_root_dir.find_sub_directory(&"src_dir"
+                                                                                   .to_string()),
+                                              "Directory not found");
+        let dest_dir_key = unwrap_option!(app_root_dir.find_sub_directory(&"dest_dir"
+                                                                               .to_string()),
+                                          "Directory not found")
+                               .get_key();
+        let src_dir = unwrap_result!(dir_helper.get(src_dir_metadata.get_key()));
+        let dest_dir = unwrap_result!(dir_helper.get(dest_dir_key));
+
+        let copied_dir_metadata = unwrap_option!(dest_dir.find_sub_directory(&"src_dir"
+                                                                                  .to_string()),
+                                                 "Directory not found");
+        assert!(copied_dir_metadata.get_key() != src_dir_metadata.get_key());
+        assert_eq!(copied_dir_metadata.get_user_metadata(),
+                   src_dir_metadata.get_user_metadata());
+        assert!(copied_dir_metadata.get_created_time() == src_dir_metadata.get_created_time());
+
+        let copied_dir = unwrap_result!(dir_helper.get(copied_dir_metadata.get_key()));
+        let src_sub_dir_key = unwrap_option!(src_dir.find_sub_directory(&"sub_dir".to_string()),
+                                             "Directory not found")
+                                  .get_key();
+        let copied_sub_dir_key = unwrap_option!(copied_dir.find_sub_directory(&"sub_dir"
+                                                                                   .to_string()),
+                                                "Directory not found")
+                                     .get_key();
+        assert!(copied_sub_dir_key != src_sub_dir_key);
+
+        // Changing the copy must leave the source untouched
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let mut copied_sub_dir = unwrap_result!(dir_helper.get(copied_sub_dir_key));
+        assert!(copied_sub_dir.find_file(&"test_file.txt".to_string()).is_some());
+        let _ = unwrap_result!(file_helper.delete("test_file.txt".to_string(),
+                                                  &mut copied_sub_dir));
+        let src_sub_dir = unwrap_result!(dir_helper.get(src_sub_dir_key));
+        assert!(src_sub_dir.find_file(&"test_file.txt".to_string()).is_some());
+    }
+}
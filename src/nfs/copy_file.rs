@@ -0,0 +1,164 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use errors::FfiError;
+use {helper, path, ParameterPacket, ResponseType, Action};
+use safe_core::nfs::helper::file_helper::FileHelper;
+
+#[derive(RustcDecodable, Debug)]
+pub struct CopyFile {
+    src_path: String,
+    is_src_path_shared: bool,
+    dest_path: String,
+    is_dest_path_shared: bool,
+    new_name: Option<String>,
+}
+
+impl Action for CopyFile {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        if (self.is_src_path_shared || self.is_dest_path_shared) && !params.safe_drive_access {
+            return Err(FfiError::PermissionDenied);
+        }
+        if let Some(ref name) = self.new_name {
+            try!(path::validate_name(name));
+        }
+
+        let (src_dir, src_file_name) =
+            try!(helper::get_directory_and_file(&params, self.is_src_path_shared, &self.src_path)
+                     .map_err(|error| error.with_path(self.src_path.clone())));
+        let dest_dir = try!(helper::get_directory_to_modify(&params,
+                                                            self.is_dest_path_shared,
+                                                            &self.dest_path)
+                            .map_err(|error| error.with_path(self.dest_path.clone())));
+        let file = try!(src_dir.find_file(&src_file_name)
+                               .map(|file| file.clone())
+                               .ok_or(FfiError::PathNotFound));
+        let file_name = self.new_name.clone().unwrap_or(src_file_name);
+
+        // The content is re-encrypted into a new datamap rather than aliasing the source's one
+        let file_helper = FileHelper::new(params.client.clone());
+        let mut writer = try!(file_helper.create(file_name,
+                                                 file.get_metadata().get_user_metadata().clone(),
+                                                 dest_dir));
+        try!(helper::copy_file_content(params.client.clone(), &file, &mut writer));
+        let (dest_dir, _) = try!(writer.close());
+        helper::invalidate_cached_directory(&params, &dest_dir);
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {Action, ParameterPacket, test_utils};
+    use errors::FfiError;
+    use safe_core::nfs::helper::file_helper::FileHelper;
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+    use safe_core::nfs::metadata::directory_key::DirectoryKey;
+
+    const TEST_FILE_NAME: &'static str = "test_file.txt";
+
+    fn create_test_file(parameter_packet: &ParameterPacket) {
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let mut writer = unwrap_result!(file_helper.create(TEST_FILE_NAME.to_string(),
+                                                           vec![1u8; 4],
+                                                           app_root_dir));
+        writer.write(&[10u8; 20], 0);
+        let _ = unwrap_result!(writer.close());
+    }
+
+    fn get_file_content(parameter_packet: &ParameterPacket,
+                        dir_key: &DirectoryKey,
+                        file_name: &str)
+                        -> Vec<u8> {
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let dir = unwrap_result!(dir_helper.get(dir_key));
+        let file = unwrap_option!(dir.find_file(&file_name.to_string()), "File not found");
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let mut reader = file_helper.read(file);
+        let size = reader.size();
+        unwrap_result!(reader.read(0, size))
+    }
+
+    #[test]
+    fn copy_file() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(true));
+
+        create_test_file(&parameter_packet);
+
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let safe_drive_dir_key = unwrap_option!(parameter_packet.clone().safe_drive_dir_key, "");
+
+        let mut request = CopyFile {
+            src_path: format!("/{}", TEST_FILE_NAME),
+            is_src_path_shared: false,
+            dest_path: "/".to_string(),
+            is_dest_path_shared: false,
+            new_name: None,
+        };
+        // A file of the same name exists in the destination
+        assert!(request.execute(parameter_packet.clone()).is_err());
+
+        request.new_name = Some("copied_file.txt".to_string());
+        assert!(request.execute(parameter_packet.clone()).is_ok());
+        assert_eq!(get_file_content(&parameter_packet, &app_root_dir_key, "copied_file.txt"),
+                   vec![10u8; 20]);
+
+        request.new_name = None;
+        request.is_dest_path_shared = true;
+        assert!(request.execute(parameter_packet.clone()).is_ok());
+        assert_eq!(get_file_content(&parameter_packet, &safe_drive_dir_key, TEST_FILE_NAME),
+                   vec![10u8; 20]);
+
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let original = unwrap_option!(app_root_dir.find_file(&TEST_FILE_NAME.to_string()),
+                                      "File not found");
+        let copy = unwrap_option!(app_root_dir.find_file(&"copied_file.txt".to_string()),
+                                  "File not found");
+        assert_eq!(copy.get_metadata().get_user_metadata(),
+                   original.get_metadata().get_user_metadata());
+
+        let mut unauthorised_packet = parameter_packet.clone();
+        unauthorised_packet.safe_drive_access = false;
+        request.new_name = Some("another_copy.txt".to_string());
+        assert!(request.execute(unauthorised_packet).is_err());
+
+        request.is_dest_path_shared = false;
+        request.new_name = Some("sub/copy.txt".to_string());
+        match request.execute(parameter_packet.clone()) {
+            Err(FfiError::InvalidPathSegment(_)) => (),
+            other => panic!("Expected InvalidPathSegment, got {:?}", other),
+        }
+        request.new_name = None;
+        request.src_path = format!("/../{}", TEST_FILE_NAME);
+        match request.execute(parameter_packet) {
+            Err(FfiError::WithDetails { error, path, .. }) => {
+                match *error {
+                    FfiError::InvalidPathSegment(_) => (),
+                    other => panic!("Expected InvalidPathSegment, got {:?}", other),
+                }
+                assert_eq!(path, Some(request.src_path.clone()));
+            }
+            other => panic!("Expected an error with the source path, got {:?}", other),
+        }
+    }
+}
@@ -15,8 +15,8 @@
 // Please review the Licences for the specific language governing permissions and limitations
 // relating to use of the SAFE Network Software.
 
-use errors::FfiError;
-use {helper, ParameterPacket, ResponseType, Action};
+use rustc_serialize::json;
+use {helper, path, ParameterPacket, ResponseType, Action};
 use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG,
                      VERSIONED_DIRECTORY_LISTING_TAG};
 use safe_core::nfs::helper::directory_helper::DirectoryHelper;
@@ -28,56 +28,94 @@ pub struct CreateDir {
     is_versioned: bool,
     user_metadata: String,
     is_path_shared: bool,
+    create_intermediate: Option<IntermediateDirParams>,
+}
+
+/// Privacy and versioning of the missing ancestors created on the way to the requested directory
+#[derive(RustcDecodable, Debug)]
+struct IntermediateDirParams {
+    is_private: bool,
+    is_versioned: bool,
+}
+
+#[derive(RustcEncodable, Debug)]
+struct CreateDirResponse {
+    created_directories: Vec<String>,
 }
 
 impl Action for CreateDir {
     fn execute(&mut self, params: ParameterPacket) -> ResponseType {
         use rustc_serialize::base64::FromBase64;
 
-        if self.is_path_shared && !params.safe_drive_access {
-            return Err(FfiError::PermissionDenied);
-        }
-
-        let mut tokens = helper::tokenise_path(&self.dir_path, false);
-        let dir_to_create = try!(tokens.pop().ok_or(FfiError::InvalidPath));
-
-        let start_dir_key = if self.is_path_shared {
-            try!(params.safe_drive_dir_key
-                       .ok_or(FfiError::from("Safe Drive directory key is not present")))
-        } else {
-            try!(params.app_root_dir_key
-                       .ok_or(FfiError::from("Application directory key is not present")))
+        let (mut tokens, dir_to_create) = try!(path::split_last(&self.dir_path));
+        let start_dir_key = try!(helper::get_start_dir_key(&params, self.is_path_shared));
+
+        let (mut parent_sub_dir, mut created_directories) = match self.create_intermediate {
+            Some(ref intermediate) => {
+                let access_level = get_access_level(intermediate.is_private);
+                try!(helper::get_or_create_final_subdirectory(&params,
+                                                              &tokens,
+                                                              &start_dir_key,
+                                                              get_tag(intermediate.is_versioned),
+                                                              intermediate.is_versioned,
+                                                              access_level))
+            }
+            None => {
+                (try!(helper::get_final_subdirectory_to_modify(&params,
+                                                               &tokens,
+                                                               Some(&start_dir_key))),
+                 Vec::new())
+            }
         };
 
-        let mut parent_sub_dir = try!(helper::get_final_subdirectory(params.client.clone(),
-                                                                     &tokens,
-                                                                     Some(&start_dir_key)));
+        let dir_helper = DirectoryHelper::new(params.client.clone());
 
-        let dir_helper = DirectoryHelper::new(params.client);
-
-        let access_level = if self.is_private {
-            AccessLevel::Private
-        } else {
-            AccessLevel::Public
-        };
-
-        let tag = if self.is_versioned {
-            VERSIONED_DIRECTORY_LISTING_TAG
-        } else {
-            UNVERSIONED_DIRECTORY_LISTING_TAG
-        };
+        let access_level = get_access_level(self.is_private);
+        let tag = get_tag(self.is_versioned);
 
         let bin_metadata = try!(parse_result!(self.user_metadata.from_base64(),
-                                              "Faild Converting from Base64."));
+                                              "Faild Converting from Base64.")
+                                    .map_err(|error| {
+                                        error.with_field("user_metadata".to_string())
+                                    }));
 
-        let _ = try!(dir_helper.create(dir_to_create,
+        let _ = try!(dir_helper.create(dir_to_create.clone(),
                                        tag,
                                        bin_metadata,
                                        self.is_versioned,
                                        access_level,
                                        Some(&mut parent_sub_dir)));
+        helper::invalidate_cached_directory(&params, &parent_sub_dir);
+
+        if self.create_intermediate.is_none() {
+            return Ok(None);
+        }
+
+        tokens.push(dir_to_create);
+        created_directories.push(format!("/{}", tokens.join("/")));
+        let response = CreateDirResponse { created_directories: created_directories };
 
-        Ok(None)
+        Ok(Some(try!(json::encode(&response))))
+    }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.dir_path.clone())
+    }
+}
+
+fn get_access_level(is_private: bool) -> AccessLevel {
+    if is_private {
+        AccessLevel::Private
+    } else {
+        AccessLevel::Public
+    }
+}
+
+fn get_tag(is_versioned: bool) -> u64 {
+    if is_versioned {
+        VERSIONED_DIRECTORY_LISTING_TAG
+    } else {
+        UNVERSIONED_DIRECTORY_LISTING_TAG
     }
 }
 
@@ -85,6 +123,7 @@ impl Action for CreateDir {
 mod test {
     use super::*;
     use {Action, test_utils};
+    use safe_core::nfs::AccessLevel;
     use safe_core::nfs::helper::directory_helper::DirectoryHelper;
 
     #[test]
@@ -97,6 +136,7 @@ mod test {
             is_versioned: false,
             user_metadata: "InNhbXBsZSBtZXRhZGF0YSI=".to_string(),
             is_path_shared: false,
+            create_intermediate: None,
         };
         assert!(request.execute(parameter_packet.clone()).is_err());
 
@@ -126,4 +166,49 @@ mod test {
         let test_dir = unwrap_result!(dir_helper.get(test_dir_key));
         assert!(test_dir.find_sub_directory(&"secondlevel".to_string()).is_some());
     }
+
+    #[test]
+    fn create_dir_with_intermediates() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+
+        let mut request = CreateDir {
+            dir_path: "/first/second/third".to_string(),
+            is_private: true,
+            is_versioned: false,
+            user_metadata: "InNhbXBsZSBtZXRhZGF0YSI=".to_string(),
+            is_path_shared: false,
+            create_intermediate: Some(super::IntermediateDirParams {
+                is_private: false,
+                is_versioned: false,
+            }),
+        };
+        let response = unwrap_option!(unwrap_result!(request.execute(parameter_packet.clone())),
+                                      "Response expected");
+        assert_eq!(response,
+                   "{\"created_directories\":[\"/first\",\"/first/second\",\
+                    \"/first/second/third\"]}");
+
+        request.dir_path = "/first/other".to_string();
+        let response = unwrap_option!(unwrap_result!(request.execute(parameter_packet.clone())),
+                                      "Response expected");
+        assert_eq!(response, "{\"created_directories\":[\"/first/other\"]}");
+
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let app_dir = unwrap_result!(dir_helper.get(&unwrap_option!(parameter_packet.clone()
+                                                                          .app_root_dir_key,
+                                                          "")));
+        let first_dir_metadata = unwrap_option!(app_dir.find_sub_directory(&"first".to_string()),
+                                                "Directory not found");
+        assert!(*first_dir_metadata.get_key().get_access_level() == AccessLevel::Public);
+        let first_dir = unwrap_result!(dir_helper.get(first_dir_metadata.get_key()));
+        assert_eq!(first_dir.get_sub_directories().len(), 2);
+        let second_dir_key = unwrap_option!(first_dir.find_sub_directory(&"second".to_string()),
+                                            "Directory not found")
+                                 .get_key();
+        let second_dir = unwrap_result!(dir_helper.get(second_dir_key));
+        let third_dir_metadata =
+            unwrap_option!(second_dir.find_sub_directory(&"third".to_string()),
+                           "Directory not found");
+        assert!(*third_dir_metadata.get_key().get_access_level() == AccessLevel::Private);
+    }
 }
@@ -15,7 +15,6 @@
 // Please review the Licences for the specific language governing permissions and limitations
 // relating to use of the SAFE Network Software.
 
-use errors::FfiError;
 use {helper, ParameterPacket, ResponseType, Action};
 use safe_core::nfs::helper::file_helper::FileHelper;
 
@@ -30,34 +29,28 @@ impl Action for CreateFile {
     fn execute(&mut self, params: ParameterPacket) -> ResponseType {
         use rustc_serialize::base64::FromBase64;
 
-        if self.is_path_shared && !params.safe_drive_access {
-            return Err(FfiError::PermissionDenied);
-        };
-
-        let start_dir_key = if self.is_path_shared {
-            try!(params.safe_drive_dir_key
-                       .ok_or(FfiError::from("Safe Drive directory key is not present")))
-        } else {
-            try!(params.app_root_dir_key
-                       .ok_or(FfiError::from("Application directory key is not present")))
-        };
-
-        let mut tokens = helper::tokenise_path(&self.file_path, false);
-        let file_name = try!(tokens.pop().ok_or(FfiError::InvalidPath));
+        let (file_directory, file_name) =
+            try!(helper::get_directory_and_file_to_modify(&params,
+                                                          self.is_path_shared,
+                                                          &self.file_path));
 
-        let file_directory = try!(helper::get_final_subdirectory(params.client.clone(),
-                                                                 &tokens,
-                                                                 Some(&start_dir_key)));
-
-        let file_helper = FileHelper::new(params.client);
+        let file_helper = FileHelper::new(params.client.clone());
         let bin_metadata = try!(parse_result!(self.user_metadata.from_base64(),
-                                              "Failed Converting from Base64."));
+                                              "Failed Converting from Base64.")
+                                    .map_err(|error| {
+                                        error.with_field("user_metadata".to_string())
+                                    }));
 
         let writer = try!(file_helper.create(file_name, bin_metadata, file_directory));
-        let _ = try!(writer.close());
+        let (file_directory, _) = try!(writer.close());
+        helper::invalidate_cached_directory(&params, &file_directory);
 
         Ok(None)
     }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.file_path.clone())
+    }
 }
 
 #[cfg(test)]
@@ -16,42 +16,107 @@
 // relating to use of the SAFE Network Software.
 
 use errors::FfiError;
-use {helper, ParameterPacket, ResponseType, Action};
+use rustc_serialize::json;
+use {helper, path, ParameterPacket, ResponseType, Action};
+use safe_core::nfs::directory_listing::DirectoryListing;
 use safe_core::nfs::helper::directory_helper::DirectoryHelper;
 
 #[derive(RustcDecodable, Debug)]
 pub struct DeleteDir {
     dir_path: String,
     is_path_shared: bool,
+    recursive: Option<bool>,
+    dry_run: Option<bool>,
+}
+
+/// Paths of everything removed along with the directory, children before their parents, and the
+/// total size of the files among them
+#[derive(RustcEncodable, Debug)]
+struct DeleteDirResponse {
+    paths: Vec<String>,
+    total_size: u64,
 }
 
 impl Action for DeleteDir {
     fn execute(&mut self, params: ParameterPacket) -> ResponseType {
-        let mut tokens = helper::tokenise_path(&self.dir_path, false);
+        // Fails with PermissionDenied for a shared path without access to SAFEDrive, before
+        // anything is collected or deleted
+        let (mut parent_dir, dir_to_delete) =
+            try!(helper::get_directory_and_file_to_modify(&params,
+                                                          self.is_path_shared,
+                                                          &self.dir_path));
         let dir_helper = DirectoryHelper::new(params.client.clone());
-        let dir_to_delete = try!(tokens.pop().ok_or(FfiError::InvalidPath));
-        let root_dir = if self.is_path_shared {
-            try!(dir_helper.get(&try!(params.safe_drive_dir_key
-                                            .ok_or(FfiError::from("Safe Drive directory key \
-                                                                   is not present")))))
-        } else {
-            try!(dir_helper.get(&try!(params.app_root_dir_key
-                                            .ok_or(FfiError::from("Application directory key \
-                                                                   is not present")))))
-        };
 
-        let mut parent_dir = if tokens.len() == 0 {
-            root_dir
-        } else {
-            try!(helper::get_final_subdirectory(params.client,
-                                                &tokens,
-                                                Some(root_dir.get_metadata()
-                                                             .get_key())))
+        let recursive = self.recursive.unwrap_or(false);
+        let dry_run = self.dry_run.unwrap_or(false);
+        if !recursive && !dry_run {
+            let _ = try!(dir_helper.delete(&mut parent_dir, &dir_to_delete));
+            helper::invalidate_cached_directory(&params, &parent_dir);
+            return Ok(None);
+        }
+
+        let dir_key = try!(parent_dir.find_sub_directory(&dir_to_delete)
+                                     .map(|metadata| metadata.get_key().clone())
+                                     .ok_or(FfiError::PathNotFound));
+        let dir = try!(dir_helper.get(&dir_key));
+
+        let dir_path = format!("/{}", try!(path::tokenise(&self.dir_path)).join("/"));
+        let mut response = DeleteDirResponse {
+            paths: Vec::new(),
+            total_size: 0,
         };
-        let _ = try!(dir_helper.delete(&mut parent_dir, &dir_to_delete));
+        try!(collect_subtree(&dir_helper, &dir, &dir_path, &mut response));
+
+        if !dry_run {
+            try!(delete_subtree(&dir_helper, dir));
+            let _ = try!(dir_helper.delete(&mut parent_dir, &dir_to_delete));
+            helper::invalidate_cached_directory(&params, &parent_dir);
+        }
+
+        Ok(Some(try!(json::encode(&response))))
+    }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.dir_path.clone())
+    }
+}
+
+fn collect_subtree(dir_helper: &DirectoryHelper,
+                   dir: &DirectoryListing,
+                   path: &String,
+                   response: &mut DeleteDirResponse)
+                   -> Result<(), FfiError> {
+    for sub_dir_metadata in dir.get_sub_directories() {
+        let sub_dir = try!(dir_helper.get(sub_dir_metadata.get_key()));
+        try!(collect_subtree(dir_helper,
+                             &sub_dir,
+                             &format!("{}/{}", path, sub_dir_metadata.get_name()),
+                             response));
+    }
 
-        Ok(None)
+    for file in dir.get_files() {
+        response.paths.push(format!("{}/{}", path, file.get_name()));
+        response.total_size += file.get_metadata().get_size();
     }
+    response.paths.push(path.clone());
+
+    Ok(())
+}
+
+// Deletes the sub-directories of `dir` deepest first. Files need no separate deletion as they are
+// only entries of the directory listing holding them and go away with it.
+fn delete_subtree(dir_helper: &DirectoryHelper, mut dir: DirectoryListing) -> Result<(), FfiError> {
+    let sub_dirs = dir.get_sub_directories()
+                      .iter()
+                      .map(|metadata| (metadata.get_name().clone(), metadata.get_key().clone()))
+                      .collect::<Vec<_>>();
+
+    for (name, key) in sub_dirs {
+        try!(delete_subtree(dir_helper, try!(dir_helper.get(&key))));
+        let _ = try!(dir_helper.delete(&mut dir, &name));
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -59,6 +124,7 @@ mod test {
     use super::*;
     use {Action, test_utils};
     use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG};
+    use safe_core::nfs::helper::file_helper::FileHelper;
     use safe_core::nfs::helper::directory_helper::DirectoryHelper;
 
     #[test]
@@ -79,6 +145,8 @@ mod test {
         let mut request = DeleteDir {
             dir_path: "/test_dir2".to_string(),
             is_path_shared: false,
+            recursive: None,
+            dry_run: None,
         };
         assert!(request.execute(parameter_packet.clone()).is_err());
         app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
@@ -90,4 +158,82 @@ mod test {
         assert_eq!(app_root_dir.get_sub_directories().len(), 0);
         assert!(request.execute(parameter_packet.clone()).is_err());
     }
+
+    #[test]
+    fn delete_dir_recursively() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let mut app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let (mut test_dir, _) = unwrap_result!(dir_helper.create("test_dir".to_string(),
+                                                                 UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                                 Vec::new(),
+                                                                 false,
+                                                                 AccessLevel::Private,
+                                                                 Some(&mut app_root_dir)));
+        let (sub_dir, _) = unwrap_result!(dir_helper.create("sub_dir".to_string(),
+                                                            UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                            Vec::new(),
+                                                            false,
+                                                            AccessLevel::Private,
+                                                            Some(&mut test_dir)));
+        let mut writer = unwrap_result!(file_helper.create("test_file.txt".to_string(),
+                                                           Vec::new(),
+                                                           sub_dir));
+        writer.write(&[1u8; 10], 0);
+        let _ = unwrap_result!(writer.close());
+
+        let mut request = DeleteDir {
+            dir_path: "/test_dir".to_string(),
+            is_path_shared: false,
+            recursive: Some(true),
+            dry_run: Some(true),
+        };
+        let expected_response = "{\"paths\":[\"/test_dir/sub_dir/test_file.txt\",\
+                                 \"/test_dir/sub_dir\",\"/test_dir\"],\"total_size\":10}";
+
+        let response = unwrap_option!(unwrap_result!(request.execute(parameter_packet.clone())),
+                                      "Response expected");
+        assert_eq!(response, expected_response);
+        app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        assert_eq!(app_root_dir.get_sub_directories().len(), 1);
+
+        request.dry_run = None;
+        let response = unwrap_option!(unwrap_result!(request.execute(parameter_packet.clone())),
+                                      "Response expected");
+        assert_eq!(response, expected_response);
+        app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        assert_eq!(app_root_dir.get_sub_directories().len(), 0);
+        assert!(dir_helper.get(test_dir.get_metadata().get_key()).is_err());
+    }
+
+    #[test]
+    fn delete_shared_dir_without_access() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let safe_drive_dir_key = unwrap_option!(parameter_packet.clone().safe_drive_dir_key, "");
+        let mut safe_drive_dir = unwrap_result!(dir_helper.get(&safe_drive_dir_key));
+        let _ = unwrap_result!(dir_helper.create("test_dir".to_string(),
+                                                 UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                 Vec::new(),
+                                                 false,
+                                                 AccessLevel::Private,
+                                                 Some(&mut safe_drive_dir)));
+
+        let mut request = DeleteDir {
+            dir_path: "/test_dir".to_string(),
+            is_path_shared: true,
+            recursive: Some(true),
+            dry_run: None,
+        };
+        match request.execute(parameter_packet) {
+            Err(FfiError::PermissionDenied) => (),
+            other => panic!("Expected PermissionDenied, got {:?}", other),
+        }
+        safe_drive_dir = unwrap_result!(dir_helper.get(&safe_drive_dir_key));
+        assert!(safe_drive_dir.find_sub_directory(&"test_dir".to_string()).is_some());
+    }
 }
@@ -15,7 +15,6 @@
 // Please review the Licences for the specific language governing permissions and limitations
 // relating to use of the SAFE Network Software.
 
-use errors::FfiError;
 use {helper, ParameterPacket, ResponseType, Action};
 use safe_core::nfs::helper::file_helper::FileHelper;
 
@@ -27,25 +26,21 @@ pub struct DeleteFile {
 
 impl Action for DeleteFile {
     fn execute(&mut self, params: ParameterPacket) -> ResponseType {
-        let start_dir_key = if self.is_path_shared {
-            try!(params.safe_drive_dir_key
-                       .ok_or(FfiError::from("Safe Drive directory key is not present")))
-        } else {
-            try!(params.app_root_dir_key
-                       .ok_or(FfiError::from("Application directory key is not present")))
-        };
-
-        let mut tokens = helper::tokenise_path(&self.file_path, false);
-        let file_name = try!(tokens.pop().ok_or(FfiError::InvalidPath));
-        let mut dir_of_file = try!(helper::get_final_subdirectory(params.client.clone(),
-                                                                  &tokens,
-                                                                  Some(&start_dir_key)));
+        let (mut dir_of_file, file_name) =
+            try!(helper::get_directory_and_file_to_modify(&params,
+                                                          self.is_path_shared,
+                                                          &self.file_path));
 
-        let file_helper = FileHelper::new(params.client);
+        let file_helper = FileHelper::new(params.client.clone());
         let _ = try!(file_helper.delete(file_name, &mut dir_of_file));
+        helper::invalidate_cached_directory(&params, &dir_of_file);
 
         Ok(None)
     }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.file_path.clone())
+    }
 }
 
 
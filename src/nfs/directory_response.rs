@@ -32,7 +32,7 @@ pub struct GetDirResponse {
 }
 
 #[derive(RustcEncodable, Debug)]
-struct DirectoryInfo {
+pub struct DirectoryInfo {
     name: String,
     is_private: bool,
     is_versioned: bool,
@@ -44,7 +44,7 @@ struct DirectoryInfo {
 }
 
 #[derive(RustcEncodable, Debug)]
-struct FileInfo {
+pub struct FileInfo {
     name: String,
     size: i64,
     user_metadata: String,
@@ -82,7 +82,7 @@ pub fn convert_to_response(directory_listing: DirectoryListing) -> GetDirRespons
     }
 }
 
-fn get_directory_info(dir_metadata: &DirectoryMetadata) -> DirectoryInfo {
+pub fn get_directory_info(dir_metadata: &DirectoryMetadata) -> DirectoryInfo {
     use rustc_serialize::base64::ToBase64;
 
     let dir_key = dir_metadata.get_key();
@@ -102,7 +102,7 @@ fn get_directory_info(dir_metadata: &DirectoryMetadata) -> DirectoryInfo {
     }
 }
 
-fn get_file_info(file_metadata: &FileMetadata) -> FileInfo {
+pub fn get_file_info(file_metadata: &FileMetadata) -> FileInfo {
     use rustc_serialize::base64::ToBase64;
 
     let created_time = file_metadata.get_created_time().to_timespec();
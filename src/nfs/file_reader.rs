@@ -0,0 +1,146 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use std::sync::{Arc, Mutex};
+
+use errors::FfiError;
+use {helper, ParameterPacket};
+use safe_core::nfs::file::File;
+use safe_core::core::client::Client;
+use safe_core::nfs::helper::file_helper::FileHelper;
+
+#[derive(RustcDecodable, Debug)]
+pub struct OpenFileReader {
+    file_path: String,
+    is_path_shared: bool,
+}
+
+impl OpenFileReader {
+    pub fn open(&self, params: ParameterPacket) -> Result<FileReader, FfiError> {
+        self.open_file(params).map_err(|error| error.with_path(self.file_path.clone()))
+    }
+
+    fn open_file(&self, params: ParameterPacket) -> Result<FileReader, FfiError> {
+        let (file_dir, file_name) = try!(helper::get_directory_and_file(&params,
+                                                                        self.is_path_shared,
+                                                                        &self.file_path));
+        let file = try!(file_dir.find_file(&file_name)
+                                .map(|file| file.clone())
+                                .ok_or(FfiError::InvalidPath));
+
+        Ok(FileReader::new(params.client, file))
+    }
+}
+
+/// Reads the content of a file piece by piece. Only the requested range is fetched and decrypted
+/// on each call, so the file as a whole is never held in memory.
+pub struct FileReader {
+    client: Arc<Mutex<Client>>,
+    file: File,
+}
+
+impl FileReader {
+    pub fn new(client: Arc<Mutex<Client>>, file: File) -> FileReader {
+        FileReader {
+            client: client,
+            file: file,
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.file.get_metadata().get_size()
+    }
+
+    /// Reads at most `length` bytes starting at `offset`. Fewer bytes are returned if the end of
+    /// the file is reached first.
+    pub fn read(&self, offset: u64, length: u64) -> Result<Vec<u8>, FfiError> {
+        let size = self.size();
+        if offset > size {
+            return Err(FfiError::SpecificParseError(format!("Offset {} is beyond the end of \
+                                                             the file of size {}",
+                                                            offset,
+                                                            size)));
+        }
+
+        let length = ::std::cmp::min(length, size - offset);
+        if length == 0 {
+            return Ok(Vec::new());
+        }
+
+        let file_helper = FileHelper::new(self.client.clone());
+        let mut reader = file_helper.read(&self.file);
+        Ok(try!(reader.read(offset, length)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {ParameterPacket, test_utils};
+    use safe_core::nfs::helper::file_helper::FileHelper;
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+    const TEST_FILE_NAME: &'static str = "test_file.txt";
+
+    fn create_test_file(parameter_packet: &ParameterPacket, data: &[u8]) {
+        let app_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let app_root_dir = unwrap_result!(dir_helper.get(&app_dir_key));
+        let mut writer = unwrap_result!(file_helper.create(TEST_FILE_NAME.to_string(),
+                                                           Vec::new(),
+                                                           app_root_dir));
+        writer.write(data, 0);
+        let _ = unwrap_result!(writer.close());
+    }
+
+    #[test]
+    fn read_file_in_pieces() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+
+        let data = (0..100).map(|i| i as u8).collect::<Vec<u8>>();
+        create_test_file(&parameter_packet, &data);
+
+        let mut request = OpenFileReader {
+            file_path: format!("/{}", TEST_FILE_NAME),
+            is_path_shared: false,
+        };
+
+        let reader = unwrap_result!(request.open(parameter_packet.clone()));
+        assert_eq!(reader.size(), data.len() as u64);
+
+        let mut read_data = Vec::new();
+        let mut offset = 0;
+        while offset < reader.size() {
+            let piece = unwrap_result!(reader.read(offset, 30));
+            assert!(piece.len() <= 30);
+            offset += piece.len() as u64;
+            read_data.extend_from_slice(&piece);
+        }
+        assert_eq!(read_data, data);
+
+        assert!(unwrap_result!(reader.read(reader.size(), 10)).is_empty());
+        assert!(reader.read(reader.size() + 1, 10).is_err());
+
+        request.file_path = "/does_not_exist".to_string();
+        assert!(request.open(parameter_packet.clone()).is_err());
+
+        request.file_path = format!("/{}", TEST_FILE_NAME);
+        request.is_path_shared = true;
+        assert!(request.open(parameter_packet).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use std::sync::{Arc, Mutex};
+
+use errors::FfiError;
+use {helper, ParameterPacket};
+use directory_cache::DirectoryCache;
+use safe_core::nfs::helper::file_helper::FileHelper;
+use safe_core::nfs::helper::writer::{Mode, Writer};
+
+#[derive(RustcDecodable, Debug)]
+pub struct OpenFileWriter {
+    file_path: String,
+    is_path_shared: bool,
+    mode: String,
+}
+
+impl OpenFileWriter {
+    pub fn open(&self, params: ParameterPacket) -> Result<FileWriter, FfiError> {
+        self.open_file(params).map_err(|error| error.with_path(self.file_path.clone()))
+    }
+
+    fn open_file(&self, params: ParameterPacket) -> Result<FileWriter, FfiError> {
+        let mode = match &self.mode[..] {
+            "overwrite" => Mode::Overwrite,
+            "modify" => Mode::Modify,
+            _ => {
+                let error = FfiError::SpecificParseError(format!("Unsupported writer mode {:?}. \
+                                                                   Expected \"overwrite\" or \
+                                                                   \"modify\".",
+                                                                  self.mode));
+                return Err(error.with_field("mode".to_string()));
+            }
+        };
+
+        let (dir_of_file, file_name) =
+            try!(helper::get_directory_and_file_to_modify(&params,
+                                                          self.is_path_shared,
+                                                          &self.file_path));
+
+        let file = try!(dir_of_file.find_file(&file_name)
+                                   .map(|file| file.clone())
+                                   .ok_or(FfiError::InvalidPath));
+
+        let file_helper = FileHelper::new(params.client.clone());
+        let writer = try!(file_helper.update_content(file, mode, dir_of_file));
+
+        Ok(FileWriter {
+            writer: writer,
+            directory_cache: params.directory_cache,
+        })
+    }
+}
+
+/// Writes the content of a file over any number of calls. The directory listing of the file is
+/// updated only once, when the writer is committed. Dropping the writer without committing it
+/// discards everything written so far.
+pub struct FileWriter {
+    writer: Writer,
+    directory_cache: Arc<Mutex<DirectoryCache>>,
+}
+
+impl FileWriter {
+    pub fn write(&mut self, data: &[u8], offset: u64) {
+        self.writer.write(data, offset);
+    }
+
+    pub fn commit(self) -> Result<(), FfiError> {
+        let (directory, _) = try!(self.writer.close());
+        unwrap_result!(self.directory_cache.lock()).invalidate(&directory);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {ParameterPacket, test_utils};
+    use safe_core::nfs::helper::file_helper::FileHelper;
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+    const TEST_FILE_NAME: &'static str = "test_file.txt";
+
+    fn create_test_file(parameter_packet: &ParameterPacket) {
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let writer = unwrap_result!(file_helper.create(TEST_FILE_NAME.to_string(),
+                                                       Vec::new(),
+                                                       app_root_dir));
+        let _ = unwrap_result!(writer.close());
+    }
+
+    fn get_file_content(parameter_packet: &ParameterPacket) -> Vec<u8> {
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let file = unwrap_option!(app_root_dir.find_file(&TEST_FILE_NAME.to_string()),
+                                  "File not found");
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let mut reader = file_helper.read(file);
+        let size = reader.size();
+        if size == 0 {
+            Vec::new()
+        } else {
+            unwrap_result!(reader.read(0, size))
+        }
+    }
+
+    #[test]
+    fn write_in_chunks_and_commit() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+
+        create_test_file(&parameter_packet);
+
+        let request = OpenFileWriter {
+            file_path: format!("/{}", TEST_FILE_NAME),
+            is_path_shared: false,
+            mode: "overwrite".to_string(),
+        };
+
+        let data = (0..250).map(|i| i as u8).collect::<Vec<u8>>();
+        let mut writer = unwrap_result!(request.open(parameter_packet.clone()));
+        for (index, chunk) in data.chunks(100).enumerate() {
+            writer.write(chunk, (index * 100) as u64);
+        }
+        assert!(get_file_content(&parameter_packet).is_empty());
+
+        unwrap_result!(writer.commit());
+        assert_eq!(get_file_content(&parameter_packet), data);
+    }
+
+    #[test]
+    fn abort_writer() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+
+        create_test_file(&parameter_packet);
+
+        let mut request = OpenFileWriter {
+            file_path: format!("/{}", TEST_FILE_NAME),
+            is_path_shared: false,
+            mode: "modify".to_string(),
+        };
+
+        {
+            let mut writer = unwrap_result!(request.open(parameter_packet.clone()));
+            writer.write(&[1u8; 10], 0);
+        }
+        assert!(get_file_content(&parameter_packet).is_empty());
+
+        request.mode = "append".to_string();
+        assert!(request.open(parameter_packet.clone()).is_err());
+
+        request.mode = "modify".to_string();
+        request.file_path = "/does_not_exist".to_string();
+        assert!(request.open(parameter_packet).is_err());
+    }
+}
@@ -15,44 +15,307 @@
 // Please review the Licences for the specific language governing permissions and limitations
 // relating to use of the SAFE Network Software.
 
+use std::cmp::Ordering;
+
 use errors::FfiError;
-use {helper, ParameterPacket, ResponseType, Action};
-use nfs::directory_response::convert_to_response;
+use rustc_serialize::{Encodable, Encoder};
+use {helper, path, ParameterPacket, ResponseType, Action};
+use nfs::directory_response::{DirectoryInfo, FileInfo, convert_to_response, get_directory_info,
+                              get_file_info};
+use nfs::get_dir_versions::get_dir_version;
+use safe_core::nfs::file::File;
+use safe_core::nfs::directory_listing::DirectoryListing;
+use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+use safe_core::nfs::metadata::directory_metadata::DirectoryMetadata;
 
 #[derive(RustcDecodable, Debug)]
 pub struct GetDir {
     dir_path: String,
     is_path_shared: bool,
+    version: Option<u64>,
+    recursive: Option<bool>,
+    max_depth: Option<u64>,
+    sort_by: Option<String>,
+    descending: Option<bool>,
+    offset: Option<u64>,
+    limit: Option<u64>,
 }
 
-impl Action for GetDir {
-    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
-        if self.is_path_shared && !params.safe_drive_access {
-            return Err(FfiError::PermissionDenied);
+/// One page of the entries of a directory and, for a recursive listing, of the directories below
+/// it. Entries are numbered in listing order, each directory being followed by its own entries
+/// and the sub-directories of a directory coming before its files, and are returned in that order.
+/// `next_offset` is the offset of the following page, or null if this page is the last.
+#[derive(RustcEncodable, Debug)]
+struct GetDirPageResponse {
+    info: DirectoryInfo,
+    entries: Vec<PageEntry>,
+    total_entries: u64,
+    next_offset: Option<u64>,
+}
+
+/// Entry of a page, `kind` being "directory" or "file" and `info` the matching details
+#[derive(RustcEncodable, Debug)]
+struct PageEntry {
+    kind: String,
+    path: String,
+    depth: u64,
+    info: EntryInfo,
+}
+
+#[derive(Debug)]
+enum EntryInfo {
+    Directory(DirectoryInfo),
+    File(FileInfo),
+}
+
+impl Encodable for EntryInfo {
+    fn encode<S: Encoder>(&self, encoder: &mut S) -> Result<(), S::Error> {
+        match *self {
+            EntryInfo::Directory(ref info) => info.encode(encoder),
+            EntryInfo::File(ref info) => info.encode(encoder),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum SortKey {
+    Name,
+    Size,
+    CreationTime,
+    ModificationTime,
+}
+
+// How the entries of a paged listing are gathered and ordered
+struct ListingOptions {
+    recursive: bool,
+    max_depth: Option<u64>,
+    sort_key: SortKey,
+    descending: bool,
+}
+
+impl ListingOptions {
+    // Whether the entries of the sub-directories found at `depth` are listed too. Only then are
+    // the listings of the sub-directories fetched.
+    fn descends_below(&self, depth: u64) -> bool {
+        self.recursive && self.max_depth.map_or(true, |max_depth| depth < max_depth)
+    }
+}
+
+enum Entry {
+    Directory(String, u64, DirectoryMetadata),
+    File(String, u64, File),
+}
+
+impl GetDir {
+    fn is_paged(&self) -> bool {
+        self.recursive.is_some() || self.max_depth.is_some() || self.sort_by.is_some() ||
+        self.descending.is_some() || self.offset.is_some() || self.limit.is_some()
+    }
+
+    fn get_listing_options(&self) -> Result<ListingOptions, FfiError> {
+        let sort_key = match self.sort_by.as_ref().map(|sort_by| &sort_by[..]) {
+            None | Some("name") => SortKey::Name,
+            Some("size") => SortKey::Size,
+            Some("creation_time") => SortKey::CreationTime,
+            Some("modification_time") => SortKey::ModificationTime,
+            Some(other) => {
+                return Err(FfiError::SpecificParseError(format!("Unsupported sort_by {:?} - \
+                                                                 expected name, size, \
+                                                                 creation_time or \
+                                                                 modification_time",
+                                                                other)))
+            }
+        };
+        let recursive = self.recursive.unwrap_or(false);
+        if recursive && self.max_depth == Some(0) {
+            return Err(FfiError::SpecificParseError("max_depth must be at least 1".to_string()));
         }
 
-        let start_dir_key = if self.is_path_shared {
-            try!(params.safe_drive_dir_key
-                       .ok_or(FfiError::from("Safe Drive directory key is not present")))
-        } else {
-            try!(params.app_root_dir_key
-                       .ok_or(FfiError::from("Application directory key is not present")))
+        Ok(ListingOptions {
+            recursive: recursive,
+            max_depth: self.max_depth,
+            sort_key: sort_key,
+            descending: self.descending.unwrap_or(false),
+        })
+    }
+
+    // Pages are ranges of offsets into the listing, which is gathered afresh on every call rather
+    // than kept between calls. Should the directory change between the calls for two pages, the
+    // entries shift and some may be skipped or returned twice; `total_entries` tells how many the
+    // listing had at the time of each call.
+    fn get_page(&self,
+                params: &ParameterPacket,
+                dir: DirectoryListing,
+                dir_path: String)
+                -> Result<GetDirPageResponse, FfiError> {
+        let options = try!(self.get_listing_options());
+        let mut entries = Vec::new();
+        try!(collect_entries(params,
+                             &DirectoryHelper::new(params.client.clone()),
+                             &dir,
+                             &dir_path,
+                             1,
+                             &options,
+                             &mut entries));
+
+        let total_entries = entries.len() as u64;
+        let offset = self.offset.unwrap_or(0);
+        let end = self.limit.map_or(total_entries, |limit| {
+            ::std::cmp::min(offset.saturating_add(limit), total_entries)
+        });
+        let mut response = GetDirPageResponse {
+            info: get_directory_info(dir.get_metadata()),
+            entries: Vec::new(),
+            total_entries: total_entries,
+            next_offset: if end < total_entries {
+                Some(end)
+            } else {
+                None
+            },
         };
+        let page_size = end.saturating_sub(offset) as usize;
+        for entry in entries.into_iter().skip(offset as usize).take(page_size) {
+            response.entries.push(match entry {
+                Entry::Directory(path, depth, metadata) => {
+                    PageEntry {
+                        kind: "directory".to_string(),
+                        path: path,
+                        depth: depth,
+                        info: EntryInfo::Directory(get_directory_info(&metadata)),
+                    }
+                }
+                Entry::File(path, depth, file) => {
+                    PageEntry {
+                        kind: "file".to_string(),
+                        path: path,
+                        depth: depth,
+                        info: EntryInfo::File(get_file_info(file.get_metadata())),
+                    }
+                }
+            });
+        }
+
+        Ok(response)
+    }
+}
+
+// Appends the entries of `dir`, found at `dir_path`, to `entries`, each sub-directory being
+// followed by its own entries while `depth` is within the maximum depth
+fn collect_entries(params: &ParameterPacket,
+                   dir_helper: &DirectoryHelper,
+                   dir: &DirectoryListing,
+                   dir_path: &String,
+                   depth: u64,
+                   options: &ListingOptions,
+                   entries: &mut Vec<Entry>)
+                   -> Result<(), FfiError> {
+    let mut sub_dirs = dir.get_sub_directories().iter().collect::<Vec<_>>();
+    sub_dirs.sort_by(|a, b| order(compare_directories(a, b, options.sort_key), options));
+    for metadata in sub_dirs {
+        let sub_dir_path = format!("{}/{}", dir_path, metadata.get_name());
+        entries.push(Entry::Directory(sub_dir_path.clone(), depth, metadata.clone()));
+        if options.descends_below(depth) {
+            let sub_dir = try!(helper::get_cached_directory(params,
+                                                            dir_helper,
+                                                            metadata.get_key()));
+            try!(collect_entries(params,
+                                 dir_helper,
+                                 &sub_dir,
+                                 &sub_dir_path,
+                                 depth + 1,
+                                 options,
+                                 entries));
+        }
+    }
+
+    let mut files = dir.get_files().iter().collect::<Vec<_>>();
+    files.sort_by(|a, b| order(compare_files(a, b, options.sort_key), options));
+    for file in files {
+        entries.push(Entry::File(format!("{}/{}", dir_path, file.get_name()),
+                                 depth,
+                                 file.clone()));
+    }
+
+    Ok(())
+}
+
+fn order(ordering: Ordering, options: &ListingOptions) -> Ordering {
+    if options.descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+// Directories have no size, so sorting by size orders them by name
+fn compare_directories(a: &DirectoryMetadata, b: &DirectoryMetadata, key: SortKey) -> Ordering {
+    let ordering = match key {
+        SortKey::Name | SortKey::Size => Ordering::Equal,
+        SortKey::CreationTime => {
+            a.get_created_time().to_timespec().cmp(&b.get_created_time().to_timespec())
+        }
+        SortKey::ModificationTime => {
+            a.get_modified_time().to_timespec().cmp(&b.get_modified_time().to_timespec())
+        }
+    };
+    match ordering {
+        Ordering::Equal => a.get_name().cmp(b.get_name()),
+        _ => ordering,
+    }
+}
+
+fn compare_files(a: &File, b: &File, key: SortKey) -> Ordering {
+    let (a, b) = (a.get_metadata(), b.get_metadata());
+    let ordering = match key {
+        SortKey::Name => Ordering::Equal,
+        SortKey::Size => a.get_size().cmp(&b.get_size()),
+        SortKey::CreationTime => {
+            a.get_created_time().to_timespec().cmp(&b.get_created_time().to_timespec())
+        }
+        SortKey::ModificationTime => {
+            a.get_modified_time().to_timespec().cmp(&b.get_modified_time().to_timespec())
+        }
+    };
+    match ordering {
+        Ordering::Equal => a.get_name().cmp(b.get_name()),
+        _ => ordering,
+    }
+}
+
+impl Action for GetDir {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let mut dir_fetched = try!(helper::get_directory(&params,
+                                                         self.is_path_shared,
+                                                         &self.dir_path));
+        if let Some(version) = self.version {
+            dir_fetched = try!(get_dir_version(params.client.clone(), &dir_fetched, version));
+        }
 
-        let tokens = helper::tokenise_path(&self.dir_path, false);
-        let dir_fetched = try!(helper::get_final_subdirectory(params.client.clone(),
-                                                              &tokens,
-                                                              Some(&start_dir_key)));
+        if self.is_paged() {
+            let dir_path = try!(path::tokenise(&self.dir_path))
+                               .iter()
+                               .fold(String::new(), |joined, name| joined + "/" + name);
+            let response = try!(self.get_page(&params, dir_fetched, dir_path));
+            return Ok(Some(try!(::rustc_serialize::json::encode(&response))));
+        }
 
         let response = convert_to_response(dir_fetched);
 
         Ok(Some(try!(::rustc_serialize::json::encode(&response))))
     }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.dir_path.clone())
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use std::time::Duration;
+
     use {Action, ParameterPacket, test_utils};
+    use rustc_serialize::json::Json;
+    use safe_core::nfs::helper::file_helper::FileHelper;
     use safe_core::nfs::helper::directory_helper::DirectoryHelper;
     use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG};
 
@@ -70,19 +333,161 @@ mod test {
                                                  Some(&mut app_root_dir)));
     }
 
+    fn get_request(dir_path: String) -> super::GetDir {
+        super::GetDir {
+            dir_path: dir_path,
+            is_path_shared: false,
+            version: None,
+            recursive: None,
+            max_depth: None,
+            sort_by: None,
+            descending: None,
+            offset: None,
+            limit: None,
+        }
+    }
+
+    // Creates /tree/a.txt (20 bytes), /tree/b.txt (10 bytes) and /tree/inner/c.txt (30 bytes)
+    fn create_test_tree(parameter_packet: &ParameterPacket) {
+        let app_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let mut app_root_dir = unwrap_result!(dir_helper.get(&app_dir_key));
+        let (mut tree, _) = unwrap_result!(dir_helper.create("tree".to_string(),
+                                                             UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                             Vec::new(),
+                                                             false,
+                                                             AccessLevel::Private,
+                                                             Some(&mut app_root_dir)));
+        let (inner, _) = unwrap_result!(dir_helper.create("inner".to_string(),
+                                                          UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                          Vec::new(),
+                                                          false,
+                                                          AccessLevel::Private,
+                                                          Some(&mut tree)));
+        let mut writer = unwrap_result!(file_helper.create("c.txt".to_string(), Vec::new(), inner));
+        writer.write(&[0u8; 30], 0);
+        let _ = unwrap_result!(writer.close());
+
+        for &(name, size) in &[("b.txt", 10), ("a.txt", 20)] {
+            let tree = unwrap_option!(unwrap_result!(dir_helper.get(&app_dir_key))
+                                          .find_sub_directory(&"tree".to_string())
+                                          .map(|metadata| metadata.get_key().clone()),
+                                      "");
+            let tree = unwrap_result!(dir_helper.get(&tree));
+            let mut writer = unwrap_result!(file_helper.create(name.to_string(), Vec::new(), tree));
+            writer.write(&vec![0u8; size], 0);
+            let _ = unwrap_result!(writer.close());
+        }
+    }
+
+    // Paths of the entries of the page in order, the total number of entries and the offset of
+    // the next page. Checks that each entry is of the kind its path suggests.
+    fn get_page(request: &mut super::GetDir,
+                parameter_packet: &ParameterPacket)
+                -> (Vec<String>, u64, Option<u64>) {
+        let response = unwrap_option!(unwrap_result!(request.execute(parameter_packet.clone())),
+                                      "");
+        let response = unwrap_result!(Json::from_str(&response));
+        let mut paths = Vec::new();
+        for entry in unwrap_option!(response.find("entries").and_then(Json::as_array), "") {
+            let path = unwrap_option!(entry.find("path").and_then(Json::as_string), "");
+            let kind = unwrap_option!(entry.find("kind").and_then(Json::as_string), "");
+            assert_eq!(kind,
+                       if path.ends_with(".txt") {
+                           "file"
+                       } else {
+                           "directory"
+                       });
+            assert!(entry.find_path(&["info", "name"]).is_some());
+            paths.push(path.to_string());
+        }
+        let total = unwrap_option!(response.find("total_entries").and_then(Json::as_u64), "");
+        (paths, total, response.find("next_offset").and_then(Json::as_u64))
+    }
+
+    fn to_strings(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|path| path.to_string()).collect()
+    }
+
+    #[test]
+    fn get_dir_pages() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        create_test_tree(&parameter_packet);
+
+        let mut request = get_request("/tree".to_string());
+        request.limit = Some(2);
+        assert_eq!(get_page(&mut request, &parameter_packet),
+                   (to_strings(&["/tree/inner", "/tree/a.txt"]), 3, Some(2)));
+        request.offset = Some(2);
+        assert_eq!(get_page(&mut request, &parameter_packet),
+                   (to_strings(&["/tree/b.txt"]), 3, None));
+
+        request.offset = None;
+        request.limit = None;
+        request.recursive = Some(true);
+        assert_eq!(get_page(&mut request, &parameter_packet),
+                   (to_strings(&["/tree/inner", "/tree/inner/c.txt", "/tree/a.txt", "/tree/b.txt"]),
+                    4,
+                    None));
+        request.max_depth = Some(1);
+        assert_eq!(get_page(&mut request, &parameter_packet).1, 3);
+
+        request.max_depth = None;
+        request.sort_by = Some("size".to_string());
+        request.descending = Some(true);
+        assert_eq!(get_page(&mut request, &parameter_packet).0,
+                   to_strings(&["/tree/inner", "/tree/inner/c.txt", "/tree/a.txt", "/tree/b.txt"]));
+        request.descending = None;
+        assert_eq!(get_page(&mut request, &parameter_packet).0,
+                   to_strings(&["/tree/inner", "/tree/inner/c.txt", "/tree/b.txt", "/tree/a.txt"]));
+
+        request.sort_by = Some("colour".to_string());
+        assert!(request.execute(parameter_packet.clone()).is_err());
+        request.sort_by = None;
+        request.max_depth = Some(0);
+        assert!(request.execute(parameter_packet).is_err());
+    }
+
+    #[test]
+    fn get_dir_page_fetches() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        unwrap_result!(parameter_packet.directory_cache.lock())
+            .configure(16, Duration::from_secs(60));
+        let get_misses = || {
+            unwrap_result!(parameter_packet.directory_cache.lock()).get_stats().misses
+        };
+        create_test_tree(&parameter_packet);
+
+        let mut request = get_request("/tree".to_string());
+        request.limit = Some(10);
+        let _ = get_page(&mut request, &parameter_packet);
+
+        // The listings on the way to /tree are cached by now and the one of /tree/inner is not
+        // needed without recursion
+        let misses = get_misses();
+        let _ = get_page(&mut request, &parameter_packet);
+        assert_eq!(get_misses(), misses);
+
+        request.recursive = Some(true);
+        let _ = get_page(&mut request, &parameter_packet);
+        assert_eq!(get_misses(), misses + 1);
+    }
+
     #[test]
     fn get_dir() {
         let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
 
         create_test_dir(&parameter_packet);
 
-        let mut request = super::GetDir {
-            dir_path: format!("/{}", TEST_DIR_NAME),
-            is_path_shared: false,
-        };
+        let mut request = get_request(format!("/{}", TEST_DIR_NAME));
 
         assert!(unwrap_result!(request.execute(parameter_packet.clone())).is_some());
 
+        request.version = Some(100);
+        assert!(request.execute(parameter_packet.clone()).is_err());
+
+        request.version = None;
         request.dir_path = "/does_not_exixts".to_string();
         assert!(request.execute(parameter_packet).is_err());
     }
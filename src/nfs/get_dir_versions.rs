@@ -0,0 +1,137 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use std::sync::{Arc, Mutex};
+
+use errors::FfiError;
+use safe_core::core::client::Client;
+use safe_core::nfs::directory_listing::DirectoryListing;
+use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+use nfs::directory_response::{convert_to_response, GetDirResponse};
+use {helper, ParameterPacket, ResponseType, Action};
+
+#[derive(RustcDecodable, Debug)]
+pub struct GetDirVersions {
+    dir_path: String,
+    is_path_shared: bool,
+}
+
+#[derive(RustcEncodable, Debug)]
+struct GetDirVersionsResponse {
+    versions: Vec<DirectoryVersion>,
+}
+
+#[derive(RustcEncodable, Debug)]
+struct DirectoryVersion {
+    version: u64,
+    directory: GetDirResponse,
+}
+
+/// Fetches the given version of a versioned directory, versions being numbered from 0 for the
+/// oldest one.
+pub fn get_dir_version(client: Arc<Mutex<Client>>,
+                       directory: &DirectoryListing,
+                       version: u64)
+                       -> Result<DirectoryListing, FfiError> {
+    let directory_helper = DirectoryHelper::new(client);
+    let mut versions = try!(directory_helper.get_versions(directory.get_key()));
+    if version >= versions.len() as u64 {
+        return Err(FfiError::VersionNotFound);
+    }
+    Ok(try!(directory_helper.get_by_version(directory.get_key().get_id(),
+                                            directory.get_metadata().get_access_level(),
+                                            versions.swap_remove(version as usize))))
+}
+
+impl Action for GetDirVersions {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let directory = try!(helper::get_directory(&params, self.is_path_shared, &self.dir_path));
+        if !directory.get_key().is_versioned() {
+            // Only versioned directories keep their history
+            return Err(FfiError::InvalidPath.with_path(self.dir_path.clone()));
+        }
+
+        let directory_helper = DirectoryHelper::new(params.client);
+        let mut response = GetDirVersionsResponse { versions: Vec::new() };
+        for (index, version) in try!(directory_helper.get_versions(directory.get_key()))
+                                    .into_iter()
+                                    .enumerate() {
+            let listing = try!(directory_helper.get_by_version(directory.get_key().get_id(),
+                                                               directory.get_metadata()
+                                                                        .get_access_level(),
+                                                               version));
+            response.versions.push(DirectoryVersion {
+                version: index as u64,
+                directory: convert_to_response(listing),
+            });
+        }
+
+        Ok(Some(try!(::rustc_serialize::json::encode(&response))))
+    }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.dir_path.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {Action, ParameterPacket, test_utils};
+    use rustc_serialize::json::Json;
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+    use safe_core::nfs::{AccessLevel, VERSIONED_DIRECTORY_LISTING_TAG};
+
+    const TEST_DIR_NAME: &'static str = "test_dir";
+
+    fn create_versioned_dir(parameter_packet: &ParameterPacket) {
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let mut app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let (mut test_dir, _) = unwrap_result!(dir_helper.create(TEST_DIR_NAME.to_string(),
+                                                                 VERSIONED_DIRECTORY_LISTING_TAG,
+                                                                 Vec::new(),
+                                                                 true,
+                                                                 AccessLevel::Private,
+                                                                 Some(&mut app_root_dir)));
+        test_dir.get_mut_metadata().set_user_metadata(vec![1u8; 4]);
+        let _ = unwrap_result!(dir_helper.update(&test_dir));
+    }
+
+    #[test]
+    fn get_dir_versions() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+
+        create_versioned_dir(&parameter_packet);
+
+        let mut request = GetDirVersions {
+            dir_path: format!("/{}", TEST_DIR_NAME),
+            is_path_shared: false,
+        };
+
+        let response = unwrap_option!(unwrap_result!(request.execute(parameter_packet.clone())),
+                                      "");
+        let json = unwrap_result!(Json::from_str(&response));
+        let versions = unwrap_option!(json.find("versions").and_then(|v| v.as_array()), "");
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[1].find("version").and_then(|v| v.as_u64()), Some(1));
+
+        // The application root directory is not versioned
+        request.dir_path = "/".to_string();
+        assert!(request.execute(parameter_packet).is_err());
+    }
+}
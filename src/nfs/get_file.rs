@@ -18,6 +18,7 @@
 use errors::FfiError;
 use rustc_serialize::json;
 use nfs::file_response::get_response;
+use nfs::get_file_versions::get_file_version;
 use {helper, ParameterPacket, ResponseType, Action};
 
 #[derive(RustcDecodable, Debug)]
@@ -27,34 +28,30 @@ pub struct GetFile {
     file_path: String,
     is_path_shared: bool,
     include_metadata: bool,
+    version: Option<u64>,
 }
 
 impl Action for GetFile {
     fn execute(&mut self, params: ParameterPacket) -> ResponseType {
         use rustc_serialize::json::ToJson;
 
-        if self.is_path_shared && !params.safe_drive_access {
-            return Err(FfiError::PermissionDenied);
-        }
-
-        let mut tokens = helper::tokenise_path(&self.file_path, false);
-        let file_name = try!(tokens.pop().ok_or(FfiError::InvalidPath));
-
-        let start_dir_key = if self.is_path_shared {
-            try!(params.safe_drive_dir_key
-                       .ok_or(FfiError::from("Safe Drive directory key is not present")))
+        // A version is looked up in the entry of the file in its directory, which is then fetched
+        // fresh as a cached listing would miss the versions added since it was cached
+        let (file_dir, file_name) = if self.version.is_some() {
+            try!(helper::get_directory_and_file_to_modify(&params,
+                                                          self.is_path_shared,
+                                                          &self.file_path))
         } else {
-            try!(params.app_root_dir_key
-                       .ok_or(FfiError::from("Application directory key is not present")))
+            try!(helper::get_directory_and_file(&params, self.is_path_shared, &self.file_path))
         };
+        let mut file = try!(file_dir.find_file(&file_name)
+                                    .map(|file| file.clone())
+                                    .ok_or(FfiError::InvalidPath));
+        if let Some(version) = self.version {
+            file = try!(get_file_version(params.client.clone(), &file, &file_dir, version));
+        }
 
-        let file_dir = try!(helper::get_final_subdirectory(params.client.clone(),
-                                                           &tokens,
-                                                           Some(&start_dir_key)));
-        let file = try!(file_dir.find_file(&file_name)
-                                .ok_or(::errors::FfiError::InvalidPath));
-
-        let response = try!(get_response(file,
+        let response = try!(get_response(&file,
                                          params.client,
                                          self.offset,
                                          self.length,
@@ -62,6 +59,10 @@ impl Action for GetFile {
 
         Ok(Some(try!(json::encode(&response.to_json()))))
     }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.file_path.clone())
+    }
 }
 
 
@@ -99,10 +100,15 @@ mod test {
             file_path: format!("/{}", TEST_FILE_NAME),
             is_path_shared: false,
             include_metadata: true,
+            version: None,
         };
 
         assert!(unwrap_result!(request.execute(parameter_packet.clone())).is_some());
 
+        request.version = Some(100);
+        assert!(request.execute(parameter_packet.clone()).is_err());
+
+        request.version = None;
         request.file_path = "/does_not_exixts".to_string();
         assert!(request.execute(parameter_packet).is_err());
     }
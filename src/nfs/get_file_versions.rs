@@ -0,0 +1,130 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use std::sync::{Arc, Mutex};
+
+use errors::FfiError;
+use safe_core::nfs::file::File;
+use safe_core::core::client::Client;
+use safe_core::nfs::directory_listing::DirectoryListing;
+use safe_core::nfs::helper::file_helper::FileHelper;
+use {helper, ParameterPacket, ResponseType, Action};
+
+#[derive(RustcDecodable, Debug)]
+pub struct GetFileVersions {
+    file_path: String,
+    is_path_shared: bool,
+}
+
+#[derive(RustcEncodable, Debug)]
+struct GetFileVersionsResponse {
+    versions: Vec<FileVersion>,
+}
+
+#[derive(RustcEncodable, Debug)]
+struct FileVersion {
+    version: u64,
+    size: i64,
+    modification_time_sec: i64,
+    modification_time_nsec: i64,
+}
+
+/// Fetches the given version of a file, versions being numbered from 0 for the oldest one.
+/// Only files in versioned directories have a history.
+pub fn get_file_version(client: Arc<Mutex<Client>>,
+                        file: &File,
+                        parent_directory: &DirectoryListing,
+                        version: u64)
+                        -> Result<File, FfiError> {
+    let file_helper = FileHelper::new(client);
+    let mut versions = try!(file_helper.get_versions(file, parent_directory));
+    if version >= versions.len() as u64 {
+        return Err(FfiError::VersionNotFound);
+    }
+    Ok(versions.swap_remove(version as usize))
+}
+
+impl Action for GetFileVersions {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        // The versions are listed in the entry of the file in its directory, which is fetched
+        // fresh as a cached listing would miss the versions added since it was cached
+        let (file_dir, file_name) =
+            try!(helper::get_directory_and_file_to_modify(&params,
+                                                          self.is_path_shared,
+                                                          &self.file_path));
+        let file = try!(file_dir.find_file(&file_name).ok_or(FfiError::InvalidPath));
+
+        let file_helper = FileHelper::new(params.client);
+        let versions = try!(file_helper.get_versions(file, &file_dir));
+        let response = GetFileVersionsResponse {
+            versions: versions.iter()
+                              .enumerate()
+                              .map(|(index, version)| {
+                                  let metadata = version.get_metadata();
+                                  let modified_time = metadata.get_modified_time().to_timespec();
+                                  FileVersion {
+                                      version: index as u64,
+                                      size: metadata.get_size() as i64,
+                                      modification_time_sec: modified_time.sec,
+                                      modification_time_nsec: modified_time.nsec as i64,
+                                  }
+                              })
+                              .collect(),
+        };
+
+        Ok(Some(try!(::rustc_serialize::json::encode(&response))))
+    }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.file_path.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {Action, test_utils};
+    use rustc_serialize::json::Json;
+
+    const TEST_DIR_NAME: &'static str = "test_dir";
+    const TEST_FILE_NAME: &'static str = "test_file.txt";
+
+    #[test]
+    fn get_file_versions() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+
+        unwrap_result!(test_utils::create_versioned_file(&parameter_packet,
+                                                         TEST_DIR_NAME,
+                                                         TEST_FILE_NAME));
+
+        let mut request = GetFileVersions {
+            file_path: format!("/{}/{}", TEST_DIR_NAME, TEST_FILE_NAME),
+            is_path_shared: false,
+        };
+
+        let response = unwrap_option!(unwrap_result!(request.execute(parameter_packet.clone())),
+                                      "");
+        let json = unwrap_result!(Json::from_str(&response));
+        let versions = unwrap_option!(json.find("versions").and_then(|v| v.as_array()), "");
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].find("size").and_then(|size| size.as_i64()), Some(10));
+        assert_eq!(versions[1].find("size").and_then(|size| size.as_i64()), Some(20));
+
+        request.file_path = format!("/{}/does_not_exist", TEST_DIR_NAME);
+        assert!(request.execute(parameter_packet).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+//! Shell style glob patterns matched against file and directory names.
+
+use errors::FfiError;
+
+/// A parsed glob pattern. `*` matches any run of characters, `?` any single character and
+/// `[...]` any character of a set, which may hold ranges such as `a-z` and is negated by a
+/// leading `!`. A `\` matches the character following it literally.
+#[derive(Debug)]
+pub struct Glob {
+    tokens: Vec<Token>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    AnyRun,
+    AnyChar,
+    Set {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+    Literal(char),
+}
+
+impl Glob {
+    /// Parses `pattern`, failing if it ends in an unterminated set or a lone `\`
+    pub fn new(pattern: &str) -> Result<Glob, FfiError> {
+        let mut tokens = Vec::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            let token = match c {
+                '*' => {
+                    // Consecutive runs match nothing more than a single one
+                    if tokens.last() == Some(&Token::AnyRun) {
+                        continue;
+                    }
+                    Token::AnyRun
+                }
+                '?' => Token::AnyChar,
+                '[' => try!(parse_set(pattern, &mut chars)),
+                '\\' => {
+                    let escaped = chars.next();
+                    Token::Literal(try!(escaped.ok_or_else(|| {
+                        get_error(pattern, "ends in a lone '\\'")
+                    })))
+                }
+                _ => Token::Literal(c),
+            };
+            tokens.push(token);
+        }
+
+        Ok(Glob { tokens: tokens })
+    }
+
+    /// Returns whether the whole of `name` matches the pattern
+    pub fn matches(&self, name: &str) -> bool {
+        // reachable[i] is set when the characters consumed so far can be matched by the first i
+        // tokens
+        let mut reachable = vec![false; self.tokens.len() + 1];
+        reachable[0] = true;
+        self.skip_empty_runs(&mut reachable);
+
+        for c in name.chars() {
+            let mut next = vec![false; self.tokens.len() + 1];
+            for (index, token) in self.tokens.iter().enumerate() {
+                if !reachable[index] {
+                    continue;
+                }
+                match *token {
+                    Token::AnyRun => next[index] = true,
+                    Token::AnyChar => next[index + 1] = true,
+                    Token::Set { negated, ref ranges } => {
+                        let in_set = ranges.iter().any(|&(low, high)| low <= c && c <= high);
+                        if in_set != negated {
+                            next[index + 1] = true;
+                        }
+                    }
+                    Token::Literal(literal) => {
+                        if literal == c {
+                            next[index + 1] = true;
+                        }
+                    }
+                }
+            }
+            self.skip_empty_runs(&mut next);
+            if !next.iter().any(|state| *state) {
+                return false;
+            }
+            reachable = next;
+        }
+
+        reachable[self.tokens.len()]
+    }
+
+    // A `*` may match no characters at all, so whatever reaches it also reaches the token after it
+    fn skip_empty_runs(&self, reachable: &mut Vec<bool>) {
+        for (index, token) in self.tokens.iter().enumerate() {
+            if reachable[index] && *token == Token::AnyRun {
+                reachable[index + 1] = true;
+            }
+        }
+    }
+}
+
+// Parses a set whose opening `[` has been consumed from `chars`
+fn parse_set(pattern: &str, chars: &mut ::std::str::Chars) -> Result<Token, FfiError> {
+    let unterminated = || get_error(pattern, "has an unterminated '['");
+    let mut negated = false;
+    let mut ranges = Vec::new();
+    let mut first = true;
+    loop {
+        let mut c = try!(chars.next().ok_or_else(&unterminated));
+        if first && c == '!' {
+            negated = true;
+            c = try!(chars.next().ok_or_else(&unterminated));
+        } else if c == ']' && !first {
+            break;
+        }
+        // A `]` right after the opening bracket is part of the set
+        first = false;
+        if c == '\\' {
+            c = try!(chars.next().ok_or_else(&unterminated));
+        }
+
+        let mut lookahead = chars.clone();
+        if lookahead.next() == Some('-') {
+            match lookahead.next() {
+                Some(']') | None => ranges.push((c, c)),
+                Some(high) => {
+                    if high < c {
+                        return Err(get_error(pattern, &format!("has the reversed range {}-{}",
+                                                               c,
+                                                               high)));
+                    }
+                    ranges.push((c, high));
+                    *chars = lookahead;
+                }
+            }
+        } else {
+            ranges.push((c, c));
+        }
+    }
+
+    Ok(Token::Set {
+        negated: negated,
+        ranges: ranges,
+    })
+}
+
+fn get_error(pattern: &str, reason: &str) -> FfiError {
+    FfiError::SpecificParseError(format!("Glob pattern {:?} {}", pattern, reason))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn matches(pattern: &str, name: &str) -> bool {
+        unwrap_result!(Glob::new(pattern)).matches(name)
+    }
+
+    #[test]
+    fn match_names() {
+        assert!(matches("*", ""));
+        assert!(matches("*", "anything.txt"));
+        assert!(matches("*.txt", "notes.txt"));
+        assert!(!matches("*.txt", "notes.txt.bak"));
+        assert!(matches("a*b*c", "aXXbYYbc"));
+        assert!(!matches("a*b*c", "aXXbYY"));
+        assert!(matches("file?.jpg", "file1.jpg"));
+        assert!(!matches("file?.jpg", "file.jpg"));
+        assert!(matches("[a-c]*", "banana"));
+        assert!(!matches("[a-c]*", "date"));
+        assert!(matches("[!a-c]*", "date"));
+        assert!(matches("[]x]", "]"));
+        assert!(matches("[a-]", "-"));
+        assert!(matches("\\*", "*"));
+        assert!(!matches("\\*", "a"));
+        assert!(matches("r\u{e9}sum\u{e9}.*", "r\u{e9}sum\u{e9}.pdf"));
+        assert!(matches("***x", "x"));
+    }
+
+    #[test]
+    fn reject_malformed_patterns() {
+        assert!(Glob::new("[abc").is_err());
+        assert!(Glob::new("[!").is_err());
+        assert!(Glob::new("abc\\").is_err());
+        assert!(Glob::new("[z-a]").is_err());
+    }
+}
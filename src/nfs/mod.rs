@@ -14,106 +14,184 @@
 //
 // Please review the Licences for the specific language governing permissions and limitations
 // relating to use of the SAFE Network Software.
-use std::fmt;
-
-use rustc_serialize::Decoder;
-use rustc_serialize::Decodable;
+use helper::{self, ActionDecoder, describe_action};
 use errors::FfiError;
+use rustc_serialize::json::Json;
+use meta::schema::{ActionSchema, FieldSchema, optional, required};
+use meta::schema::FieldType::{Bool, Integer, Object};
+use meta::schema::FieldType::String as Text;
 
+mod copy_dir;
+mod copy_file;
 mod create_dir;
 mod create_file;
 mod delete_dir;
 mod delete_file;
 mod get_dir;
+mod get_dir_versions;
 mod get_file;
+mod get_file_versions;
+mod glob;
 mod move_dir;
 mod move_file;
 mod modify_dir;
 mod modify_file;
+mod restore_dir_version;
+mod restore_file_version;
+mod search;
+mod transaction;
+pub mod file_reader;
+pub mod file_writer;
 pub mod directory_response;
 pub mod file_response;
 
-pub fn action_dispatcher<D>(action: String,
-                            params: ::ParameterPacket,
-                            decoder: &mut D)
-                            -> ::ResponseType
-    where D: Decoder,
-          D::Error: fmt::Debug
-{
-    let mut action = try!(get_action(action, decoder));
-    action.execute(params)
+pub fn action_dispatcher(action: String,
+                         params: ::ParameterPacket,
+                         request: &Json)
+                         -> ::ResponseType {
+    let mut action = try!(get_action(action, request));
+    action.execute(params).map_err(|error| {
+        match action.get_path() {
+            Some(path) => error.with_path(path),
+            None => error,
+        }
+    })
 }
 
-fn get_action<D>(action: String, decoder: &mut D) -> Result<Box<::Action>, FfiError>
-    where D: Decoder,
-          D::Error: fmt::Debug
-{
-    Ok(match &action[..] {
-        "create-dir" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            create_dir::CreateDir::decode(d)
-                                        }),
-                                        "")))
-        }
-        "create-file" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            create_file::CreateFile::decode(d)
-                                        }),
-                                        "")))
-        }
-        "delete-dir" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            delete_dir::DeleteDir::decode(d)
-                                        }),
-                                        "")))
-        }
-        "delete-file" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            delete_file::DeleteFile::decode(d)
-                                        }),
-                                        "")))
-        }
-        "get-dir" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data",
-                                                                  0,
-                                                                  |d| get_dir::GetDir::decode(d)),
-                                        "")))
-        }
-        "get-file" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            get_file::GetFile::decode(d)
-                                        }),
-                                        "")))
-        }
-        "modify-dir" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            modify_dir::ModifyDir::decode(d)
-                                        }),
-                                        "")))
-        }
-        "modify-file" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            modify_file::ModifyFile::decode(d)
-                                        }),
-                                        "")))
-        }
-        "move-dir" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            move_dir::MoveDirectory::decode(d)
-                                        }),
-                                        "")))
-        }
-        "move-file" => {
-            Box::new(try!(parse_result!(decoder.read_struct_field("data", 0, |d| {
-                                            move_file::MoveFile::decode(d)
-                                        }),
-                                        "")))
-        }
+/// Describes the actions of this module
+pub fn get_action_schemas() -> Vec<ActionSchema> {
+    get_actions().into_iter().map(|(schema, _)| schema).collect()
+}
+
+// Table of the actions of this module, which both their schemas and the decoding of requests are
+// taken from
+fn get_actions() -> Vec<(ActionSchema, ActionDecoder)> {
+    fn path_fields(path: &'static str) -> Vec<FieldSchema> {
+        vec![required(path, Text), required("is_path_shared", Bool)]
+    }
+    fn src_and_dest_fields() -> Vec<FieldSchema> {
+        vec![required("src_path", Text),
+             required("is_src_path_shared", Bool),
+             required("dest_path", Text),
+             required("is_dest_path_shared", Bool)]
+    }
+    fn with(mut fields: Vec<FieldSchema>, extra_fields: Vec<FieldSchema>) -> Vec<FieldSchema> {
+        fields.extend(extra_fields);
+        fields
+    }
+
+    let create_dir_fields =
+        vec![required("dir_path", Text),
+             required("is_private", Bool),
+             required("is_versioned", Bool),
+             required("user_metadata", Text),
+             required("is_path_shared", Bool),
+             optional("create_intermediate",
+                      Object(vec![required("is_private", Bool), required("is_versioned", Bool)]))];
+    let get_dir_fields = with(path_fields("dir_path"),
+                              vec![optional("version", Integer),
+                                   optional("recursive", Bool),
+                                   optional("max_depth", Integer),
+                                   optional("sort_by", Text),
+                                   optional("descending", Bool),
+                                   optional("offset", Integer),
+                                   optional("limit", Integer)]);
+    let get_file_fields = vec![required("offset", Integer),
+                               required("length", Integer),
+                               required("file_path", Text),
+                               required("is_path_shared", Bool),
+                               required("include_metadata", Bool),
+                               optional("version", Integer)];
+    let modify_dir_fields =
+        vec![required("dir_path", Text),
+             required("new_values",
+                      Object(vec![optional("name", Text), optional("user_metadata", Text)])),
+             required("is_path_shared", Bool)];
+    let content_fields = vec![required("bytes", Text), optional("offset", Integer)];
+    let modify_file_fields = vec![required("file_path", Text),
+                                  required("new_values",
+                                           Object(vec![optional("name", Text),
+                                                       optional("content", Object(content_fields)),
+                                                       optional("user_metadata", Text)])),
+                                  required("is_path_shared", Bool)];
+    let restore_dir_fields = with(path_fields("dir_path"), vec![required("version", Integer)]);
+    let restore_file_fields = with(path_fields("file_path"), vec![required("version", Integer)]);
+    let filter_fields = vec![optional("min_size", Integer),
+                             optional("max_size", Integer),
+                             optional("min_creation_time_sec", Integer),
+                             optional("max_creation_time_sec", Integer),
+                             optional("min_modification_time_sec", Integer),
+                             optional("max_modification_time_sec", Integer),
+                             optional("min_user_metadata", Text),
+                             optional("max_user_metadata", Text)];
+    let search_fields = with(path_fields("dir_path"),
+                             vec![optional("name_pattern", Text),
+                                  optional("include_files", Bool),
+                                  optional("include_directories", Bool),
+                                  optional("filters", Object(filter_fields))]);
+
+    vec![describe_action::<copy_dir::CopyDirectory>("copy-dir", src_and_dest_fields()),
+         describe_action::<copy_file::CopyFile>("copy-file",
+                                                with(src_and_dest_fields(),
+                                                     vec![optional("new_name", Text)])),
+         describe_action::<create_dir::CreateDir>("create-dir", create_dir_fields),
+         describe_action::<create_file::CreateFile>("create-file",
+                                                    vec![required("file_path", Text),
+                                                         required("user_metadata", Text),
+                                                         required("is_path_shared", Bool)]),
+         describe_action::<delete_dir::DeleteDir>("delete-dir",
+                                                  with(path_fields("dir_path"),
+                                                       vec![optional("recursive", Bool),
+                                                            optional("dry_run", Bool)])),
+         describe_action::<delete_file::DeleteFile>("delete-file", path_fields("file_path")),
+         describe_action::<get_dir::GetDir>("get-dir", get_dir_fields),
+         describe_action::<get_dir_versions::GetDirVersions>("get-dir-versions",
+                                                             path_fields("dir_path")),
+         describe_action::<get_file::GetFile>("get-file", get_file_fields),
+         describe_action::<get_file_versions::GetFileVersions>("get-file-versions",
+                                                               path_fields("file_path")),
+         describe_action::<modify_dir::ModifyDir>("modify-dir", modify_dir_fields),
+         describe_action::<modify_file::ModifyFile>("modify-file", modify_file_fields),
+         describe_action::<move_dir::MoveDirectory>("move-dir",
+                                                    with(src_and_dest_fields(),
+                                                         vec![required("retain_source", Bool)])),
+         describe_action::<move_file::MoveFile>("move-file",
+                                                with(src_and_dest_fields(),
+                                                     vec![required("retain_source", Bool)])),
+         describe_action::<restore_dir_version::RestoreDirVersion>("restore-dir-version",
+                                                                   restore_dir_fields),
+         describe_action::<restore_file_version::RestoreFileVersion>("restore-file-version",
+                                                                     restore_file_fields),
+         describe_action::<search::Search>("search", search_fields)]
+}
 
-        _ => {
-            return Err(FfiError::SpecificParseError(format!("Unsupported action {:?} for this \
-                                                             endpoint.",
-                                                            action)))
+fn get_action(action: String, request: &Json) -> Result<Box<::Action>, FfiError> {
+    helper::find_action(get_actions(), action, request)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::get_action;
+    use meta::schema;
+
+    #[test]
+    fn action_schemas_match_decoding() {
+        for action in get_action_schemas() {
+            for &include_optional in &[false, true] {
+                let request = schema::get_sample_request(&action.fields, include_optional, None);
+                assert!(get_action(action.name.to_string(), &request).is_ok(),
+                        "{} fails to decode its described fields",
+                        action.name);
+            }
+
+            for field in action.fields.iter().filter(|field| field.is_required) {
+                let request = schema::get_sample_request(&action.fields, true, Some(field.name));
+                assert!(get_action(action.name.to_string(), &request).is_err(),
+                        "{} decodes without its required field {}",
+                        action.name,
+                        field.name);
+            }
         }
-    })
+    }
 }
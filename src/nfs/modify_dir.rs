@@ -16,7 +16,7 @@
 // relating to use of the SAFE Network Software.
 
 use errors::FfiError;
-use {helper, ParameterPacket, ResponseType, Action};
+use {helper, path, ParameterPacket, ResponseType, Action};
 use safe_core::nfs::helper::directory_helper::DirectoryHelper;
 
 #[derive(RustcDecodable, Debug)]
@@ -34,24 +34,15 @@ impl Action for ModifyDir {
             return Err(FfiError::from("Optional parameters could not be parsed"));
         }
 
-        if self.is_path_shared && !params.safe_drive_access {
-            return Err(FfiError::PermissionDenied);
+        if let Some(ref name) = self.new_values.name {
+            try!(path::validate_name(name));
         }
 
-        let start_dir_key = if self.is_path_shared {
-            try!(params.safe_drive_dir_key
-                       .ok_or(FfiError::from("Safe Drive directory key is not present")))
-        } else {
-            try!(params.app_root_dir_key
-                       .ok_or(FfiError::from("Application directory key is not present")))
-        };
-
-        let tokens = helper::tokenise_path(&self.dir_path, false);
-        let mut dir_to_modify = try!(helper::get_final_subdirectory(params.client.clone(),
-                                                                    &tokens,
-                                                                    Some(&start_dir_key)));
+        let mut dir_to_modify = try!(helper::get_directory_to_modify(&params,
+                                                                     self.is_path_shared,
+                                                                     &self.dir_path));
 
-        let directory_helper = DirectoryHelper::new(params.client);
+        let directory_helper = DirectoryHelper::new(params.client.clone());
         if let Some(ref name) = self.new_values.name {
             dir_to_modify.get_mut_metadata().set_name(name.clone());
         }
@@ -63,9 +54,14 @@ impl Action for ModifyDir {
         }
 
         let _ = try!(directory_helper.update(&dir_to_modify));
+        helper::invalidate_cached_directory(&params, &dir_to_modify);
 
         Ok(None)
     }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.dir_path.clone())
+    }
 }
 
 #[derive(Debug, RustcDecodable)]
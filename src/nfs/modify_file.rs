@@ -16,7 +16,7 @@
 // relating to use of the SAFE Network Software.
 
 use errors::FfiError;
-use {helper, ParameterPacket, ResponseType, Action};
+use {helper, path, ParameterPacket, ResponseType, Action};
 use safe_core::nfs::helper::file_helper::FileHelper;
 use safe_core::nfs::helper::writer::Mode;
 
@@ -31,33 +31,24 @@ impl Action for ModifyFile {
     fn execute(&mut self, params: ParameterPacket) -> ResponseType {
         use rustc_serialize::base64::FromBase64;
 
-        if self.is_path_shared && !params.safe_drive_access {
-            return Err(FfiError::PermissionDenied);
-        }
-
         if self.new_values.name.is_none() && self.new_values.user_metadata.is_none() &&
            self.new_values.content.is_none() {
             return Err(FfiError::from("Optional parameters could not be parsed"));
         }
+        if let Some(ref name) = self.new_values.name {
+            try!(path::validate_name(name));
+        }
 
-        let start_dir_key = if self.is_path_shared {
-            try!(params.safe_drive_dir_key
-                       .ok_or(FfiError::from("Safe Drive directory key is not present")))
-        } else {
-            try!(params.app_root_dir_key
-                       .ok_or(FfiError::from("Application directory key is not present")))
-        };
-        let mut tokens = helper::tokenise_path(&self.file_path, false);
-        let file_name = try!(tokens.pop().ok_or(FfiError::InvalidPath));
-        let mut dir_of_file = try!(helper::get_final_subdirectory(params.client.clone(),
-                                                                  &tokens,
-                                                                  Some(&start_dir_key)));
+        let (mut dir_of_file, file_name) =
+            try!(helper::get_directory_and_file_to_modify(&params,
+                                                          self.is_path_shared,
+                                                          &self.file_path));
 
         let mut file = try!(dir_of_file.find_file(&file_name)
                                        .map(|file| file.clone())
                                        .ok_or(FfiError::InvalidPath));
 
-        let file_helper = FileHelper::new(params.client);
+        let file_helper = FileHelper::new(params.client.clone());
 
         let mut metadata_updated = false;
         if let Some(ref name) = self.new_values.name {
@@ -74,6 +65,7 @@ impl Action for ModifyFile {
 
         if metadata_updated {
             let _ = try!(file_helper.update_metadata(file.clone(), &mut dir_of_file));
+            helper::invalidate_cached_directory(&params, &dir_of_file);
         }
 
         if let Some(ref file_content_params) = self.new_values.content {
@@ -85,11 +77,16 @@ impl Action for ModifyFile {
             let bytes = try!(parse_result!(file_content_params.bytes.from_base64(),
                                            "Failed to convert from base64"));
             writer.write(&bytes[..], offset);
-            let _ = try!(writer.close());
+            let (dir_of_file, _) = try!(writer.close());
+            helper::invalidate_cached_directory(&params, &dir_of_file);
         }
 
         Ok(None)
     }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.file_path.clone())
+    }
 }
 
 #[derive(RustcDecodable, Debug)]
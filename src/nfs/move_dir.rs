@@ -16,7 +16,8 @@
 // relating to use of the SAFE Network Software.
 
 use errors::FfiError;
-use {helper, ParameterPacket, ResponseType, Action};
+use {helper, path, ParameterPacket, ResponseType, Action};
+use nfs::transaction::Transaction;
 use safe_core::nfs::helper::directory_helper::DirectoryHelper;
 use safe_core::nfs::directory_listing::DirectoryListing;
 use safe_core::nfs::errors::NfsError::DirectoryAlreadyExistsWithSameName;
@@ -30,43 +31,33 @@ pub struct MoveDirectory {
     retain_source: bool,
 }
 
-impl MoveDirectory {
-    fn get_directory(&self,
-                     params: &ParameterPacket,
-                     shared: bool,
-                     path: &String)
-                     -> Result<DirectoryListing, FfiError> {
-        let start_dir_key = if shared {
-            try!(params.clone()
-                       .safe_drive_dir_key
-                       .ok_or(FfiError::from("Safe Drive directory key is not present")))
-        } else {
-            try!(params.clone()
-                       .app_root_dir_key
-                       .ok_or(FfiError::from("Application directory key is not present")))
-        };
-
-        let tokens = helper::tokenise_path(path, false);
-        helper::get_final_subdirectory(params.client.clone(), &tokens, Some(&start_dir_key))
-    }
-}
-
 impl Action for MoveDirectory {
     fn execute(&mut self, params: ParameterPacket) -> ResponseType {
         if (self.is_src_path_shared || self.is_dest_path_shared) && !params.safe_drive_access {
             return Err(FfiError::PermissionDenied);
         }
+        // Moving a directory into its own subtree would detach the subtree from the root
+        if self.is_src_path_shared == self.is_dest_path_shared {
+            let src_tokens = try!(path::tokenise(&self.src_path));
+            let dest_tokens = try!(path::tokenise(&self.dest_path));
+            if dest_tokens.starts_with(&src_tokens) {
+                return Err(FfiError::InvalidPath);
+            }
+        }
+
         let directory_helper = DirectoryHelper::new(params.client.clone());
-        let mut src_dir = try!(self.get_directory(&params,
-                                                  self.is_src_path_shared,
-                                                  &self.src_path));
-        let mut dest_dir = try!(self.get_directory(&params,
-                                                   self.is_dest_path_shared,
-                                                   &self.dest_path));
+        let mut src_dir = try!(helper::get_directory_to_modify(&params,
+                                                               self.is_src_path_shared,
+                                                               &self.src_path)
+                               .map_err(|error| error.with_path(self.src_path.clone())));
+        let mut dest_dir = try!(helper::get_directory_to_modify(&params,
+                                                                self.is_dest_path_shared,
+                                                                &self.dest_path)
+                                .map_err(|error| error.with_path(self.dest_path.clone())));
         if dest_dir.find_sub_directory(src_dir.get_metadata().get_name()).is_some() {
             return Err(FfiError::from(DirectoryAlreadyExistsWithSameName));
         }
-        let org_parent_of_src_dir = src_dir.get_metadata().get_key().clone();
+
         if self.retain_source {
             let name = src_dir.get_metadata().get_name().clone();
             let user_metadata = src_dir.get_metadata().get_user_metadata().clone();
@@ -97,20 +88,82 @@ impl Action for MoveDirectory {
                    });
             dir.get_mut_metadata().set_created_time(created_time);
             dir.get_mut_metadata().set_modified_time(modified_time);
-            src_dir = dir;
-        } else {
-            src_dir.get_mut_metadata()
-                   .set_parent_dir_key(Some(dest_dir.get_metadata().get_key().clone()));
+            dest_dir.upsert_sub_directory(dir.get_metadata().clone());
+            let _ = try!(directory_helper.update(&dest_dir));
+            helper::invalidate_cached_directory(&params, &dest_dir);
+            let _ = try!(directory_helper.update(&dir));
+            return Ok(None);
         }
+
+        // The root directories have no parent and can not be moved
+        let mut src_parent_dir = {
+            let src_parent_dir_key = try!(src_dir.get_metadata()
+                                                 .get_parent_dir_key()
+                                                 .ok_or(FfiError::InvalidPath));
+            try!(directory_helper.get(src_parent_dir_key))
+        };
+        try!(src_parent_dir.remove_sub_directory(src_dir.get_metadata().get_name()));
+        src_dir.get_mut_metadata()
+               .set_parent_dir_key(Some(dest_dir.get_metadata().get_key().clone()));
         dest_dir.upsert_sub_directory(src_dir.get_metadata().clone());
-        let _ = try!(directory_helper.update(&dest_dir));
-        let _ = try!(directory_helper.update(&src_dir));
-        if !self.retain_source {
-            let mut parent_of_src_dir = try!(directory_helper.get(&org_parent_of_src_dir));
-            try!(parent_of_src_dir.remove_sub_directory(dest_dir.get_metadata()
-                                                                .get_name()));
-            let _ = try!(directory_helper.update(&parent_of_src_dir));
-        }
+
+        let mut transaction = Transaction::new(&params);
+        transaction.stage(dest_dir);
+        transaction.stage(src_dir);
+        transaction.stage(src_parent_dir);
+        try!(transaction.commit());
+
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {Action, test_utils};
+    use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG};
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+    #[test]
+    fn move_dir() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let mut app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        for name in &["src_dir", "dest_dir"] {
+            let _ = unwrap_result!(dir_helper.create(name.to_string(),
+                                                     UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                     Vec::new(),
+                                                     false,
+                                                     AccessLevel::Private,
+                                                     Some(&mut app_root_dir)));
+        }
+
+        let mut request = MoveDirectory {
+            src_path: "/src_dir".to_string(),
+            is_src_path_shared: false,
+            dest_path: "/src_dir".to_string(),
+            is_dest_path_shared: false,
+            retain_source: false,
+        };
+        // Into its own subtree
+        assert!(request.execute(parameter_packet.clone()).is_err());
+
+        request.dest_path = "/dest_dir".to_string();
+        assert!(request.execute(parameter_packet.clone()).is_ok());
+
+        let app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        assert!(app_root_dir.find_sub_directory(&"src_dir".to_string()).is_none());
+        let dest_dir_key = unwrap_option!(app_root_dir.find_sub_directory(&"dest_dir".to_string()),
+                                          "Directory not found")
+                               .get_key()
+                               .clone();
+        let dest_dir = unwrap_result!(dir_helper.get(&dest_dir_key));
+        let src_dir_key = unwrap_option!(dest_dir.find_sub_directory(&"src_dir".to_string()),
+                                         "Directory not found")
+                              .get_key()
+                              .clone();
+        let src_dir = unwrap_result!(dir_helper.get(&src_dir_key));
+        assert_eq!(src_dir.get_metadata().get_parent_dir_key(), Some(&dest_dir_key));
+    }
+}
@@ -17,8 +17,7 @@
 
 use errors::FfiError;
 use {helper, ParameterPacket, ResponseType, Action};
-use safe_core::nfs::helper::directory_helper::DirectoryHelper;
-use safe_core::nfs::directory_listing::DirectoryListing;
+use nfs::transaction::Transaction;
 use safe_core::nfs::errors::NfsError::DirectoryAlreadyExistsWithSameName;
 
 #[derive(RustcDecodable, Debug)]
@@ -30,62 +29,28 @@ pub struct MoveFile {
     retain_source: bool,
 }
 
-impl MoveFile {
-    fn get_directory_and_file(&self,
-                              params: &ParameterPacket,
-                              shared: bool,
-                              path: &String)
-                              -> Result<(DirectoryListing, String), FfiError> {
-        let start_dir_key = if shared {
-            try!(params.clone()
-                       .safe_drive_dir_key
-                       .ok_or(FfiError::from("Safe Drive directory key is not present")))
-        } else {
-            try!(params.clone()
-                       .app_root_dir_key
-                       .ok_or(FfiError::from("Application directory key is not present")))
-        };
-
-        let mut tokens = helper::tokenise_path(path, false);
-        let file_name = try!(tokens.pop().ok_or(FfiError::PathNotFound));
-        let directory_listing = try!(helper::get_final_subdirectory(params.client.clone(),
-                                                                    &tokens,
-                                                                    Some(&start_dir_key)));
-        Ok((directory_listing, file_name))
-    }
-
-    fn get_directory(&self,
-                     params: &ParameterPacket,
-                     shared: bool,
-                     path: &String)
-                     -> Result<DirectoryListing, FfiError> {
-        let start_dir_key = if shared {
-            try!(params.clone()
-                       .safe_drive_dir_key
-                       .ok_or(FfiError::from("Safe Drive directory key is not present")))
-        } else {
-            try!(params.clone()
-                       .app_root_dir_key
-                       .ok_or(FfiError::from("Application directory key is not present")))
-        };
-
-        let tokens = helper::tokenise_path(path, false);
-        helper::get_final_subdirectory(params.client.clone(), &tokens, Some(&start_dir_key))
+impl Action for MoveFile {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let transaction = Transaction::new(&params);
+        self.move_file(params, transaction)
     }
 }
 
-impl Action for MoveFile {
-    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+impl MoveFile {
+    // Moves the file, updating both directories through `transaction`
+    fn move_file(&self, params: ParameterPacket, mut transaction: Transaction) -> ResponseType {
         if (self.is_src_path_shared || self.is_dest_path_shared) && !params.safe_drive_access {
             return Err(FfiError::PermissionDenied);
         }
-        let directory_helper = DirectoryHelper::new(params.client.clone());
-        let (mut src_dir, src_file_name) = try!(self.get_directory_and_file(&params,
-                                                                            self.is_src_path_shared,
-                                                                            &self.src_path));
-        let mut dest_dir = try!(self.get_directory(&params,
-                                                   self.is_dest_path_shared,
-                                                   &self.dest_path));
+        let (mut src_dir, src_file_name) =
+            try!(helper::get_directory_and_file_to_modify(&params,
+                                                          self.is_src_path_shared,
+                                                          &self.src_path)
+                     .map_err(|error| error.with_path(self.src_path.clone())));
+        let mut dest_dir = try!(helper::get_directory_to_modify(&params,
+                                                                self.is_dest_path_shared,
+                                                                &self.dest_path)
+                                .map_err(|error| error.with_path(self.dest_path.clone())));
         if dest_dir.find_file(&src_file_name).is_some() {
             return Err(FfiError::from(DirectoryAlreadyExistsWithSameName));
         }
@@ -94,11 +59,68 @@ impl Action for MoveFile {
             None => return Err(FfiError::PathNotFound),
         };
         dest_dir.upsert_file(file);
-        let _ = try!(directory_helper.update(&dest_dir));
+        transaction.stage(dest_dir);
         if !self.retain_source {
             try!(src_dir.remove_file(&src_file_name));
-            let _ = try!(directory_helper.update(&src_dir));
+            transaction.stage(src_dir);
         }
+        try!(transaction.commit());
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {Action, test_utils};
+    use nfs::transaction::{self, Transaction};
+    use safe_core::nfs::AccessLevel;
+    use safe_core::nfs::UNVERSIONED_DIRECTORY_LISTING_TAG;
+    use safe_core::nfs::helper::file_helper::FileHelper;
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+    const TEST_FILE_NAME: &'static str = "test_file.txt";
+
+    #[test]
+    fn move_file_rolled_back() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+
+        let mut app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let (dest_dir, _) = unwrap_result!(dir_helper.create("dest".to_string(),
+                                                             UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                             Vec::new(),
+                                                             false,
+                                                             AccessLevel::Private,
+                                                             Some(&mut app_root_dir)));
+        let writer = unwrap_result!(file_helper.create(TEST_FILE_NAME.to_string(),
+                                                       Vec::new(),
+                                                       app_root_dir));
+        let _ = unwrap_result!(writer.close());
+
+        let mut request = MoveFile {
+            src_path: format!("/{}", TEST_FILE_NAME),
+            is_src_path_shared: false,
+            dest_path: "/dest".to_string(),
+            is_dest_path_shared: false,
+            retain_source: false,
+        };
+        // The file is added to the destination, then removing it from the source fails
+        let updater = transaction::get_failing_updater(&parameter_packet, vec![true, false]);
+        let failing_transaction = Transaction::with_updater(&parameter_packet, updater);
+        assert!(request.move_file(parameter_packet.clone(), failing_transaction).is_err());
+
+        let app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let dest_dir = unwrap_result!(dir_helper.get(dest_dir.get_key()));
+        assert!(app_root_dir.find_file(&TEST_FILE_NAME.to_string()).is_some());
+        assert!(dest_dir.find_file(&TEST_FILE_NAME.to_string()).is_none());
+
+        assert!(request.execute(parameter_packet).is_ok());
+        let app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let dest_dir = unwrap_result!(dir_helper.get(dest_dir.get_key()));
+        assert!(app_root_dir.find_file(&TEST_FILE_NAME.to_string()).is_none());
+        assert!(dest_dir.find_file(&TEST_FILE_NAME.to_string()).is_some());
+    }
+}
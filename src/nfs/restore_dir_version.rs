@@ -0,0 +1,127 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use errors::FfiError;
+use {helper, ParameterPacket, ResponseType, Action};
+use nfs::get_dir_versions::get_dir_version;
+use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+#[derive(RustcDecodable, Debug)]
+pub struct RestoreDirVersion {
+    dir_path: String,
+    is_path_shared: bool,
+    version: u64,
+}
+
+impl Action for RestoreDirVersion {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let directory = try!(helper::get_directory_to_modify(&params,
+                                                             self.is_path_shared,
+                                                             &self.dir_path));
+        if !directory.get_key().is_versioned() {
+            // Only versioned directories keep their history
+            return Err(FfiError::InvalidPath.with_path(self.dir_path.clone()));
+        }
+
+        // The entries of the old version are saved as a new version, keeping the current
+        // metadata so that the name the parent knows the directory by stays valid. Sub-directories
+        // have listings of their own and are not rolled back. Those created since the restored
+        // version drop out of the listing but are not deleted, so restoring the version which
+        // lists them brings them back with their content.
+        let mut restored = try!(get_dir_version(params.client.clone(), &directory, self.version));
+        *restored.get_mut_metadata() = directory.get_metadata().clone();
+
+        let directory_helper = DirectoryHelper::new(params.client.clone());
+        let _ = try!(directory_helper.update(&restored));
+        helper::invalidate_cached_directory(&params, &restored);
+
+        Ok(None)
+    }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.dir_path.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {Action, ParameterPacket, test_utils};
+    use errors::FfiError;
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+    use safe_core::nfs::{AccessLevel, VERSIONED_DIRECTORY_LISTING_TAG};
+    use safe_core::nfs::directory_listing::DirectoryListing;
+
+    const TEST_DIR_NAME: &'static str = "test_dir";
+
+    fn get_test_dir(parameter_packet: &ParameterPacket) -> DirectoryListing {
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let test_dir_key = unwrap_option!(app_root_dir.find_sub_directory(&TEST_DIR_NAME
+                                                                               .to_string()),
+                                          "Directory not found")
+                               .get_key();
+        unwrap_result!(dir_helper.get(test_dir_key))
+    }
+
+    #[test]
+    fn restore_dir_version() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let mut app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let (mut test_dir, _) = unwrap_result!(dir_helper.create(TEST_DIR_NAME.to_string(),
+                                                                 VERSIONED_DIRECTORY_LISTING_TAG,
+                                                                 Vec::new(),
+                                                                 true,
+                                                                 AccessLevel::Private,
+                                                                 Some(&mut app_root_dir)));
+        let _ = unwrap_result!(dir_helper.create("sub_dir".to_string(),
+                                                 VERSIONED_DIRECTORY_LISTING_TAG,
+                                                 Vec::new(),
+                                                 true,
+                                                 AccessLevel::Private,
+                                                 Some(&mut test_dir)));
+        assert_eq!(get_test_dir(&parameter_packet).get_sub_directories().len(), 1);
+
+        let mut request = RestoreDirVersion {
+            dir_path: format!("/{}", TEST_DIR_NAME),
+            is_path_shared: false,
+            version: 0,
+        };
+        assert!(request.execute(parameter_packet.clone()).is_ok());
+        assert!(get_test_dir(&parameter_packet).get_sub_directories().is_empty());
+
+        request.version = 100;
+        assert!(request.execute(parameter_packet.clone()).is_err());
+
+        request.dir_path = "/".to_string();
+        request.version = 0;
+        match request.execute(parameter_packet) {
+            Err(FfiError::WithDetails { error, path, .. }) => {
+                match *error {
+                    FfiError::InvalidPath => (),
+                    other => panic!("Expected InvalidPath, got {:?}", other),
+                }
+                assert_eq!(path, Some(request.dir_path.clone()));
+            }
+            other => panic!("Expected an error with the directory path, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use errors::FfiError;
+use {helper, ParameterPacket, ResponseType, Action};
+use nfs::get_file_versions::get_file_version;
+use safe_core::nfs::helper::writer::Mode;
+use safe_core::nfs::helper::file_helper::FileHelper;
+
+#[derive(RustcDecodable, Debug)]
+pub struct RestoreFileVersion {
+    file_path: String,
+    is_path_shared: bool,
+    version: u64,
+}
+
+impl Action for RestoreFileVersion {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let (file_dir, file_name) =
+            try!(helper::get_directory_and_file_to_modify(&params,
+                                                          self.is_path_shared,
+                                                          &self.file_path));
+        let file = try!(file_dir.find_file(&file_name)
+                                .map(|file| file.clone())
+                                .ok_or(FfiError::InvalidPath));
+        let old_version = try!(get_file_version(params.client.clone(),
+                                                &file,
+                                                &file_dir,
+                                                self.version));
+
+        // The old content is written as a new version so that the history stays intact
+        let file_helper = FileHelper::new(params.client.clone());
+        let mut writer = try!(file_helper.update_content(file, Mode::Overwrite, file_dir));
+        try!(helper::copy_file_content(params.client.clone(), &old_version, &mut writer));
+        let (file_dir, _) = try!(writer.close());
+        helper::invalidate_cached_directory(&params, &file_dir);
+
+        Ok(None)
+    }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.file_path.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {Action, ParameterPacket, test_utils};
+    use safe_core::nfs::helper::file_helper::FileHelper;
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+    const TEST_DIR_NAME: &'static str = "test_dir";
+    const TEST_FILE_NAME: &'static str = "test_file.txt";
+
+    fn get_file_content(parameter_packet: &ParameterPacket) -> Vec<u8> {
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let test_dir_key = unwrap_option!(app_root_dir.find_sub_directory(&TEST_DIR_NAME
+                                                                               .to_string()),
+                                          "Directory not found")
+                               .get_key();
+        let test_dir = unwrap_result!(dir_helper.get(test_dir_key));
+        let file = unwrap_option!(test_dir.find_file(&TEST_FILE_NAME.to_string()),
+                                  "File not found");
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let mut reader = file_helper.read(file);
+        let size = reader.size();
+        unwrap_result!(reader.read(0, size))
+    }
+
+    #[test]
+    fn restore_file_version() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+
+        unwrap_result!(test_utils::create_versioned_file(&parameter_packet,
+                                                         TEST_DIR_NAME,
+                                                         TEST_FILE_NAME));
+        assert_eq!(get_file_content(&parameter_packet), vec![2u8; 20]);
+
+        let mut request = RestoreFileVersion {
+            file_path: format!("/{}/{}", TEST_DIR_NAME, TEST_FILE_NAME),
+            is_path_shared: false,
+            version: 0,
+        };
+        assert!(request.execute(parameter_packet.clone()).is_ok());
+        assert_eq!(get_file_content(&parameter_packet), vec![1u8; 10]);
+
+        request.version = 100;
+        assert!(request.execute(parameter_packet).is_err());
+    }
+}
@@ -0,0 +1,331 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use errors::FfiError;
+use rustc_serialize::json;
+use nfs::glob::Glob;
+use {helper, path, ParameterPacket, ResponseType, Action};
+use nfs::directory_response::{DirectoryInfo, FileInfo, get_directory_info, get_file_info};
+use safe_core::nfs::directory_listing::DirectoryListing;
+use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+#[derive(RustcDecodable, Debug)]
+pub struct Search {
+    dir_path: String,
+    is_path_shared: bool,
+    name_pattern: Option<String>,
+    include_files: Option<bool>,
+    include_directories: Option<bool>,
+    filters: Option<SearchFilters>,
+}
+
+/// Inclusive bounds an entry must fall within to be reported. Directories have no size, so they
+/// are left out whenever a size bound is given. User metadata is given in base64 and compared
+/// byte by byte.
+#[derive(RustcDecodable, Debug, Default)]
+struct SearchFilters {
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    min_creation_time_sec: Option<i64>,
+    max_creation_time_sec: Option<i64>,
+    min_modification_time_sec: Option<i64>,
+    max_modification_time_sec: Option<i64>,
+    min_user_metadata: Option<String>,
+    max_user_metadata: Option<String>,
+}
+
+/// Entries below the starting directory matching the search, each with its full path
+#[derive(RustcEncodable, Debug)]
+struct SearchResponse {
+    files: Vec<FileMatch>,
+    directories: Vec<DirectoryMatch>,
+}
+
+#[derive(RustcEncodable, Debug)]
+struct FileMatch {
+    path: String,
+    info: FileInfo,
+}
+
+#[derive(RustcEncodable, Debug)]
+struct DirectoryMatch {
+    path: String,
+    info: DirectoryInfo,
+}
+
+// Bounds of `SearchFilters` with the user metadata decoded
+#[derive(Default)]
+struct Bounds {
+    size: (Option<u64>, Option<u64>),
+    creation_time_sec: (Option<i64>, Option<i64>),
+    modification_time_sec: (Option<i64>, Option<i64>),
+    user_metadata: (Option<Vec<u8>>, Option<Vec<u8>>),
+}
+
+impl Bounds {
+    fn new(filters: &Option<SearchFilters>) -> Result<Bounds, FfiError> {
+        use rustc_serialize::base64::FromBase64;
+
+        let filters = match *filters {
+            Some(ref filters) => filters,
+            None => return Ok(Bounds::default()),
+        };
+        let decode = |metadata: &Option<String>| -> Result<Option<Vec<u8>>, FfiError> {
+            match *metadata {
+                Some(ref base64) => {
+                    Ok(Some(try!(parse_result!(base64.from_base64(),
+                                               "Failed to convert from base64"))))
+                }
+                None => Ok(None),
+            }
+        };
+
+        Ok(Bounds {
+            size: (filters.min_size, filters.max_size),
+            creation_time_sec: (filters.min_creation_time_sec, filters.max_creation_time_sec),
+            modification_time_sec: (filters.min_modification_time_sec,
+                                    filters.max_modification_time_sec),
+            user_metadata: (try!(decode(&filters.min_user_metadata)),
+                            try!(decode(&filters.max_user_metadata))),
+        })
+    }
+
+    fn contain(&self,
+               size: Option<u64>,
+               creation_time_sec: i64,
+               modification_time_sec: i64,
+               user_metadata: &Vec<u8>)
+               -> bool {
+        fn within<T: PartialOrd>(value: &T, bounds: &(Option<T>, Option<T>)) -> bool {
+            bounds.0.as_ref().map_or(true, |min| min <= value) &&
+            bounds.1.as_ref().map_or(true, |max| value <= max)
+        }
+
+        let size_within = match size {
+            Some(size) => within(&size, &self.size),
+            None => self.size.0.is_none() && self.size.1.is_none(),
+        };
+        size_within && within(&creation_time_sec, &self.creation_time_sec) &&
+        within(&modification_time_sec, &self.modification_time_sec) &&
+        within(user_metadata, &self.user_metadata)
+    }
+}
+
+// What to look for during the walk of the directory tree
+struct Criteria {
+    glob: Glob,
+    include_files: bool,
+    include_directories: bool,
+    bounds: Bounds,
+}
+
+impl Action for Search {
+    fn execute(&mut self, params: ParameterPacket) -> ResponseType {
+        let criteria = Criteria {
+            glob: try!(Glob::new(self.name_pattern.as_ref().map_or("*", |pattern| &pattern[..]))),
+            include_files: self.include_files.unwrap_or(true),
+            include_directories: self.include_directories.unwrap_or(true),
+            bounds: try!(Bounds::new(&self.filters)),
+        };
+
+        let start_dir = try!(helper::get_directory(&params, self.is_path_shared, &self.dir_path));
+
+        let mut response = SearchResponse {
+            files: Vec::new(),
+            directories: Vec::new(),
+        };
+        let start_path = try!(path::tokenise(&self.dir_path))
+                             .iter()
+                             .fold(String::new(), |joined, name| joined + "/" + name);
+        try!(search_directory(&params,
+                              &DirectoryHelper::new(params.client.clone()),
+                              &start_dir,
+                              &start_path,
+                              &criteria,
+                              &mut response));
+
+        Ok(Some(try!(json::encode(&response))))
+    }
+
+    fn get_path(&self) -> Option<String> {
+        Some(self.dir_path.clone())
+    }
+}
+
+// Adds the matching entries below `dir`, found at `dir_path`, to `response`. Every sub-directory
+// is searched whether it matches or not.
+fn search_directory(params: &ParameterPacket,
+                    dir_helper: &DirectoryHelper,
+                    dir: &DirectoryListing,
+                    dir_path: &String,
+                    criteria: &Criteria,
+                    response: &mut SearchResponse)
+                    -> Result<(), FfiError> {
+    if criteria.include_files {
+        for file in dir.get_files() {
+            let metadata = file.get_metadata();
+            if criteria.glob.matches(metadata.get_name()) &&
+               criteria.bounds.contain(Some(metadata.get_size()),
+                                       metadata.get_created_time().to_timespec().sec,
+                                       metadata.get_modified_time().to_timespec().sec,
+                                       metadata.get_user_metadata()) {
+                response.files.push(FileMatch {
+                    path: format!("{}/{}", dir_path, metadata.get_name()),
+                    info: get_file_info(metadata),
+                });
+            }
+        }
+    }
+
+    for metadata in dir.get_sub_directories() {
+        let sub_dir_path = format!("{}/{}", dir_path, metadata.get_name());
+        if criteria.include_directories && criteria.glob.matches(metadata.get_name()) &&
+           criteria.bounds.contain(None,
+                                   metadata.get_created_time().to_timespec().sec,
+                                   metadata.get_modified_time().to_timespec().sec,
+                                   metadata.get_user_metadata()) {
+            response.directories.push(DirectoryMatch {
+                path: sub_dir_path.clone(),
+                info: get_directory_info(metadata),
+            });
+        }
+
+        let sub_dir = try!(helper::get_cached_directory(params, dir_helper, metadata.get_key()));
+        try!(search_directory(params, dir_helper, &sub_dir, &sub_dir_path, criteria, response));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::SearchFilters;
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use rustc_serialize::json::Json;
+    use rustc_serialize::base64::ToBase64;
+    use {Action, ParameterPacket, test_utils};
+    use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG};
+    use safe_core::nfs::helper::file_helper::FileHelper;
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+    // Creates /docs/notes.txt (10 bytes), /docs/old/notes.md (100 bytes) and /photo.jpg (1000
+    // bytes), each with its name as user metadata
+    fn create_test_tree(parameter_packet: &ParameterPacket) {
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+        let file_helper = FileHelper::new(parameter_packet.client.clone());
+        let mut app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        let (mut docs, _) = unwrap_result!(dir_helper.create("docs".to_string(),
+                                                             UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                             Vec::new(),
+                                                             false,
+                                                             AccessLevel::Private,
+                                                             Some(&mut app_root_dir)));
+        let (old, _) = unwrap_result!(dir_helper.create("old".to_string(),
+                                                        UNVERSIONED_DIRECTORY_LISTING_TAG,
+                                                        Vec::new(),
+                                                        false,
+                                                        AccessLevel::Private,
+                                                        Some(&mut docs)));
+        let app_root_dir = unwrap_result!(dir_helper.get(&app_root_dir_key));
+        for (name, size, dir) in vec![("notes.txt", 10, docs),
+                                      ("notes.md", 100, old),
+                                      ("photo.jpg", 1000, app_root_dir)] {
+            let mut writer = unwrap_result!(file_helper.create(name.to_string(),
+                                                               name.as_bytes().to_vec(),
+                                                               dir));
+            writer.write(&vec![0u8; size], 0);
+            let _ = unwrap_result!(writer.close());
+        }
+    }
+
+    // Paths of the matching files followed by those of the matching directories
+    fn get_paths(request: &mut Search, parameter_packet: &ParameterPacket) -> Vec<String> {
+        let response = unwrap_option!(unwrap_result!(request.execute(parameter_packet.clone())),
+                                      "");
+        let response = unwrap_result!(Json::from_str(&response));
+        let mut paths = Vec::new();
+        for key in &["files", "directories"] {
+            for entry in unwrap_option!(response.find(key).and_then(Json::as_array), "") {
+                paths.push(unwrap_option!(entry.find("path").and_then(Json::as_string), "")
+                               .to_string());
+            }
+        }
+        paths
+    }
+
+    fn to_base64(metadata: &str) -> Option<String> {
+        Some(metadata.as_bytes().to_base64(::config::get_base64_config()))
+    }
+
+    #[test]
+    fn search() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        create_test_tree(&parameter_packet);
+
+        let mut request = Search {
+            dir_path: "/".to_string(),
+            is_path_shared: false,
+            name_pattern: Some("notes.*".to_string()),
+            include_files: None,
+            include_directories: None,
+            filters: None,
+        };
+        assert_eq!(get_paths(&mut request, &parameter_packet),
+                   vec!["/docs/notes.txt".to_string(), "/docs/old/notes.md".to_string()]);
+
+        request.dir_path = "/docs/old".to_string();
+        assert_eq!(get_paths(&mut request, &parameter_packet),
+                   vec!["/docs/old/notes.md".to_string()]);
+
+        request.dir_path = "/".to_string();
+        request.name_pattern = None;
+        request.include_files = Some(false);
+        assert_eq!(get_paths(&mut request, &parameter_packet),
+                   vec!["/docs".to_string(), "/docs/old".to_string()]);
+
+        request.include_files = None;
+        let mut filters = SearchFilters::default();
+        filters.min_size = Some(50);
+        filters.max_size = Some(500);
+        request.filters = Some(filters);
+        assert_eq!(get_paths(&mut request, &parameter_packet),
+                   vec!["/docs/old/notes.md".to_string()]);
+
+        let mut filters = SearchFilters::default();
+        filters.min_user_metadata = to_base64("notes");
+        filters.max_user_metadata = to_base64("notf");
+        request.filters = Some(filters);
+        assert_eq!(get_paths(&mut request, &parameter_packet),
+                   vec!["/docs/notes.txt".to_string(), "/docs/old/notes.md".to_string()]);
+
+        let mut filters = SearchFilters::default();
+        let now = unwrap_result!(SystemTime::now().duration_since(UNIX_EPOCH));
+        filters.min_modification_time_sec = Some(now.as_secs() as i64 + 3600);
+        request.filters = Some(filters);
+        assert!(get_paths(&mut request, &parameter_packet).is_empty());
+
+        request.filters = None;
+        request.name_pattern = Some("[abc".to_string());
+        assert!(request.execute(parameter_packet.clone()).is_err());
+
+        request.name_pattern = None;
+        request.is_path_shared = true;
+        assert!(request.execute(parameter_packet).is_err());
+    }
+}
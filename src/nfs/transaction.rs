@@ -0,0 +1,199 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use std::sync::{Arc, Mutex};
+
+use ParameterPacket;
+use errors::FfiError;
+use directory_cache::DirectoryCache;
+use safe_core::nfs::errors::NfsError;
+use safe_core::nfs::directory_listing::DirectoryListing;
+use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+/// Writes a directory listing to the network
+pub type Updater = Box<Fn(&DirectoryListing) -> Result<(), NfsError>>;
+
+/// Stages the changes to several directory listings for them to be applied together. If applying
+/// any of them fails, those already applied are reverted, so that an operation spanning several
+/// directories either takes effect in full or not at all. Should reverting fail as well, the
+/// error names the directories left inconsistent.
+pub struct Transaction {
+    directory_helper: DirectoryHelper,
+    directory_cache: Arc<Mutex<DirectoryCache>>,
+    staged: Vec<DirectoryListing>,
+    updater: Updater,
+}
+
+impl Transaction {
+    pub fn new(params: &ParameterPacket) -> Transaction {
+        let directory_helper = DirectoryHelper::new(params.client.clone());
+        Transaction::with_updater(params,
+                                  Box::new(move |directory: &DirectoryListing| {
+                                      directory_helper.update(directory).map(|_| ())
+                                  }))
+    }
+
+    /// Transaction writing the directories with `updater`, which lets tests make any update of a
+    /// commit or of its roll back fail
+    pub fn with_updater(params: &ParameterPacket, updater: Updater) -> Transaction {
+        Transaction {
+            directory_helper: DirectoryHelper::new(params.client.clone()),
+            directory_cache: params.directory_cache.clone(),
+            staged: Vec::new(),
+            updater: updater,
+        }
+    }
+
+    /// Stages `directory` to be updated on commit. Staging a directory again replaces the listing
+    /// staged before for it.
+    pub fn stage(&mut self, directory: DirectoryListing) {
+        let key = directory.get_metadata().get_key().clone();
+        match self.staged.iter().position(|staged| *staged.get_metadata().get_key() == key) {
+            Some(index) => self.staged[index] = directory,
+            None => self.staged.push(directory),
+        }
+    }
+
+    /// Updates the staged directories in the order they were staged. On failure the directories
+    /// updated already are restored to the listings they had before and the error is returned. If
+    /// any of them can not be restored, `FfiError::RollbackFailed` naming them is returned instead.
+    pub fn commit(self) -> Result<(), FfiError> {
+        let mut applied = Vec::with_capacity(self.staged.len());
+        for directory in &self.staged {
+            // Fetched right before its update to be restored should any later update fail
+            let result = self.directory_helper
+                             .get(directory.get_metadata().get_key())
+                             .and_then(|original| (self.updater)(directory).map(|_| original));
+            unwrap_result!(self.directory_cache.lock()).invalidate(directory);
+            match result {
+                Ok(original) => applied.push(original),
+                Err(error) => return Err(self.roll_back(&applied, FfiError::from(error))),
+            }
+        }
+
+        Ok(())
+    }
+
+    // Restores `originals` after the commit failed with `cause`. Returns the error to report for
+    // the commit, which is `cause` unless some of the directories could not be restored.
+    fn roll_back(&self, originals: &[DirectoryListing], cause: FfiError) -> FfiError {
+        let mut failures = Vec::new();
+        for original in originals.iter().rev() {
+            unwrap_result!(self.directory_cache.lock()).invalidate(original);
+            if let Err(error) = (self.updater)(original) {
+                failures.push(format!("{:?} ({:?})", original.get_metadata().get_name(), error));
+            }
+        }
+
+        if failures.is_empty() {
+            cause
+        } else {
+            FfiError::rollback_failed(format!("directories {}", failures.join(", ")), cause)
+        }
+    }
+}
+
+/// Updater failing the updates for which the next of `outcomes` is `false`. The updates after
+/// those succeed or fail on their own.
+#[cfg(test)]
+pub fn get_failing_updater(params: &ParameterPacket, outcomes: Vec<bool>) -> Updater {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+
+    let directory_helper = DirectoryHelper::new(params.client.clone());
+    let outcomes = RefCell::new(outcomes.into_iter().collect::<VecDeque<_>>());
+    Box::new(move |directory: &DirectoryListing| {
+        if outcomes.borrow_mut().pop_front().unwrap_or(true) {
+            directory_helper.update(directory).map(|_| ())
+        } else {
+            Err(NfsError::Unexpected("Forced failure of the update".to_string()))
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_utils;
+    use errors::FfiError;
+    use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG};
+    use safe_core::nfs::directory_listing::DirectoryListing;
+    use safe_core::nfs::helper::directory_helper::DirectoryHelper;
+
+    fn create_test_dir(directory_helper: &DirectoryHelper,
+                       parent: &mut DirectoryListing,
+                       name: &str)
+                       -> DirectoryListing {
+        let tag = UNVERSIONED_DIRECTORY_LISTING_TAG;
+        let (directory, _) = unwrap_result!(directory_helper.create(name.to_string(),
+                                                                    tag,
+                                                                    vec![],
+                                                                    false,
+                                                                    AccessLevel::Private,
+                                                                    Some(parent)));
+        directory
+    }
+
+    #[test]
+    fn commit_and_roll_back() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        let app_root_dir_key = unwrap_option!(parameter_packet.clone().app_root_dir_key, "");
+        let directory_helper = DirectoryHelper::new(parameter_packet.client.clone());
+
+        let mut app_root_dir = unwrap_result!(directory_helper.get(&app_root_dir_key));
+        let mut first_dir = create_test_dir(&directory_helper, &mut app_root_dir, "first");
+        let mut second_dir = create_test_dir(&directory_helper, &mut app_root_dir, "second");
+
+        first_dir.get_mut_metadata().set_user_metadata(vec![1u8]);
+        second_dir.get_mut_metadata().set_user_metadata(vec![2u8]);
+        let mut transaction = Transaction::new(&parameter_packet);
+        transaction.stage(first_dir.clone());
+        transaction.stage(second_dir.clone());
+        assert!(transaction.commit().is_ok());
+
+        let first_dir = unwrap_result!(directory_helper.get(first_dir.get_metadata().get_key()));
+        assert_eq!(*first_dir.get_metadata().get_user_metadata(), vec![1u8]);
+
+        // The deleted directory fails to be fetched once the first one was updated, which is then
+        // reverted
+        let _ = unwrap_result!(directory_helper.delete(&mut app_root_dir, &"second".to_string()));
+        let mut changed_first_dir = first_dir.clone();
+        changed_first_dir.get_mut_metadata().set_user_metadata(vec![3u8]);
+        let mut transaction = Transaction::new(&parameter_packet);
+        transaction.stage(changed_first_dir);
+        transaction.stage(second_dir);
+        assert!(transaction.commit().is_err());
+
+        let first_dir = unwrap_result!(directory_helper.get(first_dir.get_metadata().get_key()));
+        assert_eq!(*first_dir.get_metadata().get_user_metadata(), vec![1u8]);
+
+        // Reverting the first directory fails as well after the update of the third one failed
+        let third_dir = create_test_dir(&directory_helper, &mut app_root_dir, "third");
+        let mut changed_first_dir = first_dir.clone();
+        changed_first_dir.get_mut_metadata().set_user_metadata(vec![4u8]);
+        let updater = get_failing_updater(&parameter_packet, vec![true, false, false]);
+        let mut transaction = Transaction::with_updater(&parameter_packet, updater);
+        transaction.stage(changed_first_dir);
+        transaction.stage(third_dir);
+        match transaction.commit() {
+            Err(FfiError::RollbackFailed { inconsistent, .. }) => {
+                assert!(inconsistent.contains("\"first\""), "{}", inconsistent);
+            }
+            other => panic!("Expected the failure of the roll back, got {:?}", other),
+        }
+    }
+}
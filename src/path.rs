@@ -0,0 +1,174 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+//! Normalisation and validation of the paths and names given by the NFS and DNS requests.
+
+use errors::FfiError;
+
+/// Maximum length in bytes of a file or directory name
+pub const MAX_NAME_LENGTH: usize = 255;
+
+/// Splits `path` into the names leading from the root to its target. Empty segments (repeated,
+/// leading or trailing slashes) and `.` are skipped and `..` drops the previous name, so
+/// `/a/./b//../c/` gives `["a", "c"]`. Fails naming the offending segment if a `..` would leave
+/// the root or a name breaks the rules of `validate_name`.
+pub fn tokenise(path: &str) -> Result<Vec<String>, FfiError> {
+    let mut tokens: Vec<String> = Vec::new();
+    for (index, segment) in path.split('/').enumerate() {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                if tokens.pop().is_none() {
+                    return Err(FfiError::InvalidPathSegment(format!("Segment {} (\"..\") of \
+                                                                     {:?} leads outside the \
+                                                                     root directory",
+                                                                    index + 1,
+                                                                    path)));
+                }
+            }
+            _ => {
+                if let Err(reason) = check_name(segment) {
+                    return Err(FfiError::InvalidPathSegment(format!("Segment {} ({:?}) of {:?} \
+                                                                     {}",
+                                                                    index + 1,
+                                                                    segment,
+                                                                    path,
+                                                                    reason)));
+                }
+                tokens.push(segment.to_string());
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Splits `path` as `tokenise` does and separates the name of its target from the names of the
+/// directories leading to it. Fails if `path` names the root directory itself.
+pub fn split_last(path: &str) -> Result<(Vec<String>, String), FfiError> {
+    let mut tokens = try!(tokenise(path));
+    match tokens.pop() {
+        Some(name) => Ok((tokens, name)),
+        None => {
+            Err(FfiError::InvalidPathSegment(format!("{:?} names the root directory rather than \
+                                                      an entry in it",
+                                                     path)))
+        }
+    }
+}
+
+/// Checks a new name given to a file or directory. It must be non-empty, at most
+/// `MAX_NAME_LENGTH` bytes long, neither `.` nor `..` and free of `/`, `\` and control characters.
+pub fn validate_name(name: &str) -> Result<(), FfiError> {
+    if name.contains('/') {
+        return Err(FfiError::InvalidPathSegment(format!("Name {:?} contains '/'", name)));
+    }
+    if name == "." || name == ".." {
+        return Err(FfiError::InvalidPathSegment(format!("Name {:?} is reserved", name)));
+    }
+    check_name(name).map_err(|reason| FfiError::InvalidPathSegment(format!("Name {:?} {}",
+                                                                            name,
+                                                                            reason)))
+}
+
+// Returns why `name` is not a valid name, given it holds no '/'
+fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("is empty".to_string());
+    }
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(format!("is {} bytes long, the maximum is {}", name.len(), MAX_NAME_LENGTH));
+    }
+    if let Some(invalid) = name.chars().find(|c| c.is_control() || *c == '\\') {
+        return Err(format!("contains the invalid character {:?}", invalid));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use errors::FfiError;
+
+    fn get_message(result: Result<Vec<String>, FfiError>) -> String {
+        match result {
+            Err(FfiError::InvalidPathSegment(message)) => message,
+            other => panic!("Expected InvalidPathSegment, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn canonicalise_paths() {
+        let expected = vec!["a".to_string(), "c".to_string()];
+        assert_eq!(unwrap_result!(tokenise("/a/./b//../c/")), expected);
+        assert_eq!(unwrap_result!(tokenise("a/c")), expected);
+        assert!(unwrap_result!(tokenise("/")).is_empty());
+        assert!(unwrap_result!(tokenise("")).is_empty());
+        assert!(unwrap_result!(tokenise("/a/..")).is_empty());
+        assert_eq!(unwrap_result!(tokenise("/a b/.hidden/...")),
+                   vec!["a b".to_string(), ".hidden".to_string(), "...".to_string()]);
+    }
+
+    #[test]
+    fn reject_invalid_paths() {
+        let message = get_message(tokenise("/a/../../b"));
+        assert!(message.contains("Segment 4"));
+        assert!(message.contains("outside the root"));
+
+        let message = get_message(tokenise("/a/b\\c"));
+        assert!(message.contains("Segment 3"));
+        assert!(message.contains("'\\\\'"));
+
+        let message = get_message(tokenise("/a\u{7}"));
+        assert!(message.contains("invalid character"));
+
+        let long_name = (0..MAX_NAME_LENGTH + 1).map(|_| "x").collect::<String>();
+        assert!(unwrap_result!(tokenise(&long_name[1..])).len() == 1);
+        let message = get_message(tokenise(&format!("/a/{}", long_name)));
+        assert!(message.contains("Segment 3"));
+        assert!(message.contains("256 bytes long"));
+    }
+
+    #[test]
+    fn split_paths() {
+        let (tokens, name) = unwrap_result!(split_last("/a/b/file.txt/"));
+        assert_eq!(tokens, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(name, "file.txt");
+
+        let (tokens, name) = unwrap_result!(split_last("file.txt"));
+        assert!(tokens.is_empty());
+        assert_eq!(name, "file.txt");
+
+        for root in &["", "/", "/a/..", "/./"] {
+            match split_last(root) {
+                Err(FfiError::InvalidPathSegment(message)) => assert!(message.contains("root")),
+                other => panic!("Expected InvalidPathSegment, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn validate_names() {
+        assert!(validate_name("file.txt").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name(".").is_err());
+        assert!(validate_name("..").is_err());
+        assert!(validate_name("a/b").is_err());
+        assert!(validate_name("a\nb").is_err());
+    }
+}
@@ -19,9 +19,13 @@ use std::sync::{Arc, Mutex};
 
 use errors::FfiError;
 use ParameterPacket;
+use directory_cache::DirectoryCache;
 use safe_core::core::utility::test_utils;
+use safe_core::nfs::helper::writer::Mode;
+use safe_core::nfs::helper::file_helper::FileHelper;
 use safe_core::nfs::helper::directory_helper::DirectoryHelper;
-use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG};
+use safe_core::nfs::{AccessLevel, UNVERSIONED_DIRECTORY_LISTING_TAG,
+                     VERSIONED_DIRECTORY_LISTING_TAG};
 
 #[allow(unused)]
 pub fn get_parameter_packet(has_safe_drive_access: bool) -> Result<ParameterPacket, FfiError> {
@@ -45,6 +49,7 @@ pub fn get_parameter_packet(has_safe_drive_access: bool) -> Result<ParameterPack
         app_root_dir_key: Some(test_app.get_key().clone()),
         safe_drive_access: has_safe_drive_access,
         safe_drive_dir_key: Some(safe_drive.get_key().clone()),
+        directory_cache: Arc::new(Mutex::new(DirectoryCache::default())),
     })
 }
 
@@ -58,5 +63,40 @@ pub fn get_unregistered_parameter_packet() -> Result<ParameterPacket, FfiError>
         app_root_dir_key: None,
         safe_drive_access: false,
         safe_drive_dir_key: None,
+        directory_cache: Arc::new(Mutex::new(DirectoryCache::default())),
     })
 }
+
+/// Creates the versioned directory `dir_name` in the application directory holding the file
+/// `file_name` with two versions: 10 bytes of 1s, then 20 bytes of 2s
+#[allow(unused)]
+pub fn create_versioned_file(parameter_packet: &ParameterPacket,
+                             dir_name: &str,
+                             file_name: &str)
+                             -> Result<(), FfiError> {
+    let app_root_dir_key = try!(parameter_packet.app_root_dir_key
+                                                .clone()
+                                                .ok_or(FfiError::from("Application directory \
+                                                                       key is not present")));
+    let file_helper = FileHelper::new(parameter_packet.client.clone());
+    let dir_helper = DirectoryHelper::new(parameter_packet.client.clone());
+    let mut app_root_dir = try!(dir_helper.get(&app_root_dir_key));
+    let (test_dir, _) = try!(dir_helper.create(dir_name.to_string(),
+                                               VERSIONED_DIRECTORY_LISTING_TAG,
+                                               Vec::new(),
+                                               true,
+                                               AccessLevel::Private,
+                                               Some(&mut app_root_dir)));
+    let mut writer = try!(file_helper.create(file_name.to_string(), Vec::new(), test_dir));
+    writer.write(&[1u8; 10], 0);
+    let (test_dir, _) = try!(writer.close());
+
+    let file = try!(test_dir.find_file(&file_name.to_string())
+                            .map(|file| file.clone())
+                            .ok_or(FfiError::PathNotFound));
+    let mut writer = try!(file_helper.update_content(file, Mode::Overwrite, test_dir));
+    writer.write(&[2u8; 20], 0);
+    let _ = try!(writer.close());
+
+    Ok(())
+}
@@ -0,0 +1,275 @@
+// Copyright 2015 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under (1) the MaidSafe.net Commercial License,
+// version 1.0 or later, or (2) The General Public License (GPL), version 3, depending on which
+// licence you accepted on initial access to the Software (the "Licences").
+//
+// By contributing code to the SAFE Network Software, or to this project generally, you agree to be
+// bound by the terms of the MaidSafe Contributor Agreement, version 1.0.  This, along with the
+// Licenses can be found in the root directory of this project at LICENSE, COPYING and CONTRIBUTOR.
+//
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.
+//
+// Please review the Licences for the specific language governing permissions and limitations
+// relating to use of the SAFE Network Software.
+
+use std::ptr;
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, mpsc};
+use std::sync::mpsc::Sender;
+use std::sync::atomic::{ATOMIC_USIZE_INIT, AtomicUsize, Ordering};
+
+use errors::FfiError;
+use directory_cache::DirectoryCache;
+use libc::{c_void, int32_t};
+use safe_core::core::client::Client;
+use maidsafe_utilities::thread::RaiiThreadJoiner;
+
+/// Invoked on completion of an asynchronous request with the user data given on submission, the
+/// result code and the response bytes along with their size.
+pub type CompletionCallback = extern "C" fn(*mut c_void, int32_t, *const u8, int32_t);
+
+static NEXT_POOL_ID: AtomicUsize = ATOMIC_USIZE_INIT;
+
+thread_local! {
+    // Id of the pool the current thread is a worker of, if any
+    static CURRENT_POOL_ID: Cell<Option<usize>> = Cell::new(None)
+}
+
+struct UserData(*mut c_void);
+
+// The user data is never dereferenced by this crate, only handed back to the caller's callback.
+#[allow(unsafe_code)]
+unsafe impl Send for UserData {}
+
+struct Request {
+    id: u64,
+    payload: String,
+    user_data: UserData,
+    callback: CompletionCallback,
+}
+
+/// Executes JSON requests on a fixed number of worker threads sharing one client
+pub struct WorkerPool {
+    id: usize,
+    request_tx: Option<Sender<Request>>,
+    queued_requests: Arc<Mutex<HashSet<u64>>>,
+    next_request_id: AtomicUsize,
+    _raii_joiners: Vec<RaiiThreadJoiner>,
+}
+
+impl WorkerPool {
+    pub fn new(client: Arc<Mutex<Client>>,
+               directory_cache: Arc<Mutex<DirectoryCache>>,
+               worker_count: usize)
+               -> WorkerPool {
+        let (request_tx, request_rx) = mpsc::channel::<Request>();
+        let request_rx = Arc::new(Mutex::new(request_rx));
+        let queued_requests = Arc::new(Mutex::new(HashSet::new()));
+        let id = NEXT_POOL_ID.fetch_add(1, Ordering::SeqCst);
+
+        let raii_joiners = (0..worker_count)
+                               .map(|_| {
+                                   let client = client.clone();
+                                   let directory_cache = directory_cache.clone();
+                                   let request_rx = request_rx.clone();
+                                   let queued_requests = queued_requests.clone();
+                                   RaiiThreadJoiner::new(thread!("FfiAsyncWorker", move || {
+                                       CURRENT_POOL_ID.with(|pool_id| pool_id.set(Some(id)));
+                                       run_worker(client,
+                                                  directory_cache,
+                                                  request_rx,
+                                                  queued_requests)
+                                   }))
+                               })
+                               .collect();
+
+        WorkerPool {
+            id: id,
+            request_tx: Some(request_tx),
+            queued_requests: queued_requests,
+            next_request_id: AtomicUsize::new(0),
+            _raii_joiners: raii_joiners,
+        }
+    }
+
+    pub fn submit(&self,
+                  payload: String,
+                  user_data: *mut c_void,
+                  callback: CompletionCallback)
+                  -> Result<u64, FfiError> {
+        let request_id = self.next_request_id.fetch_add(1, Ordering::SeqCst) as u64;
+
+        let request_tx = try!(self.request_tx
+                                  .as_ref()
+                                  .ok_or(FfiError::from("Worker pool has been shut down")));
+        let _ = unwrap_result!(self.queued_requests.lock()).insert(request_id);
+        let request = Request {
+            id: request_id,
+            payload: payload,
+            user_data: UserData(user_data),
+            callback: callback,
+        };
+        if request_tx.send(request).is_err() {
+            let _ = unwrap_result!(self.queued_requests.lock()).remove(&request_id);
+            return Err(FfiError::from("Worker pool has been shut down"));
+        }
+
+        Ok(request_id)
+    }
+
+    /// Whether the current thread is one of the workers of this pool, as it is while a completion
+    /// callback runs. The pool can not be dropped from such a thread, as dropping it joins the
+    /// workers.
+    pub fn is_worker_thread(&self) -> bool {
+        CURRENT_POOL_ID.with(|pool_id| pool_id.get() == Some(self.id))
+    }
+
+    pub fn cancel(&self, request_id: u64) -> Result<(), FfiError> {
+        if unwrap_result!(self.queued_requests.lock()).remove(&request_id) {
+            Ok(())
+        } else {
+            Err(FfiError::InvalidRequestId)
+        }
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        // Requests still in the queue are reported as cancelled by the workers, which then exit
+        // once the queue is drained and are joined as the pool goes out of scope.
+        unwrap_result!(self.queued_requests.lock()).clear();
+        self.request_tx = None;
+    }
+}
+
+fn run_worker(client: Arc<Mutex<Client>>,
+              directory_cache: Arc<Mutex<DirectoryCache>>,
+              request_rx: Arc<Mutex<mpsc::Receiver<Request>>>,
+              queued_requests: Arc<Mutex<HashSet<u64>>>) {
+    loop {
+        let request = match unwrap_result!(request_rx.lock()).recv() {
+            Ok(request) => request,
+            Err(_) => break,
+        };
+
+        if !unwrap_result!(queued_requests.lock()).remove(&request.id) {
+            (request.callback)(request.user_data.0,
+                               FfiError::RequestCancelled.into(),
+                               ptr::null(),
+                               0);
+            continue;
+        }
+
+        match ::execute_payload(client.clone(), directory_cache.clone(), &request.payload) {
+            Ok(response) => {
+                let data = match response {
+                    Some(response) => response.into_bytes(),
+                    None => Vec::with_capacity(0),
+                };
+                (request.callback)(request.user_data.0, 0, data.as_ptr(), data.len() as int32_t);
+            }
+            Err(error) => {
+                let decorator = ::std::iter::repeat('-').take(50).collect::<String>();
+                error!("\n\n {}\n| {:?}\n {}\n\n", decorator, error, decorator);
+                (request.callback)(request.user_data.0, error.into(), ptr::null(), 0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(unsafe_code)]
+
+    use super::*;
+
+    use std::slice;
+    use std::sync::Mutex;
+    use std::sync::mpsc::{self, Sender};
+
+    use test_utils;
+    use errors::FfiError;
+    use libc::{c_void, int32_t};
+    use rustc_serialize::base64::ToBase64;
+    use maidsafe_utilities::serialisation::serialise;
+
+    extern "C" fn callback(user_data: *mut c_void,
+                           result: int32_t,
+                           data: *const u8,
+                           size: int32_t) {
+        let tx = unsafe { &*(user_data as *const Mutex<Sender<(i32, Vec<u8>)>>) };
+        let response = if size == 0 {
+            Vec::new()
+        } else {
+            unsafe { slice::from_raw_parts(data, size as usize) }.to_vec()
+        };
+        unwrap_result!(unwrap_result!(tx.lock()).send((result, response)));
+    }
+
+    #[test]
+    fn execute_requests() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+        let app_dir_key = unwrap_option!(parameter_packet.app_root_dir_key, "");
+        let base64_app_dir_key = unwrap_result!(serialise(&app_dir_key))
+                                     .to_base64(::config::get_base64_config());
+
+        let (tx, rx) = mpsc::channel();
+        let tx = Mutex::new(tx);
+        let tx_ptr: *const Mutex<Sender<(i32, Vec<u8>)>> = &tx;
+        let user_data = tx_ptr as *mut c_void;
+
+        let worker_pool = WorkerPool::new(parameter_packet.client.clone(),
+                                          parameter_packet.directory_cache.clone(),
+                                          2);
+
+        let payload = format!("{{\"module\": \"nfs\", \"action\": \"get-dir\", \"app_dir_key\": \
+                               \"{}\", \"data\": {{\"dir_path\": \"/\", \"is_path_shared\": \
+                               false}}}}",
+                              base64_app_dir_key);
+        let _ = unwrap_result!(worker_pool.submit(payload, user_data, callback));
+        let (result, response) = unwrap_result!(rx.recv());
+        assert_eq!(result, 0);
+        assert!(!response.is_empty());
+
+        let _ = unwrap_result!(worker_pool.submit("{".to_string(), user_data, callback));
+        let (result, response) = unwrap_result!(rx.recv());
+        assert!(result != 0);
+        assert!(response.is_empty());
+    }
+
+    #[test]
+    fn cancel_requests() {
+        let parameter_packet = unwrap_result!(test_utils::get_parameter_packet(false));
+
+        let (tx, rx) = mpsc::channel();
+        let tx = Mutex::new(tx);
+        let tx_ptr: *const Mutex<Sender<(i32, Vec<u8>)>> = &tx;
+        let user_data = tx_ptr as *mut c_void;
+
+        {
+            // Without workers the request stays queued until the pool is dropped
+            let worker_pool = WorkerPool::new(parameter_packet.client.clone(),
+                                              parameter_packet.directory_cache.clone(),
+                                              0);
+            let request_id = unwrap_result!(worker_pool.submit("{}".to_string(),
+                                                               user_data,
+                                                               callback));
+            assert!(worker_pool.cancel(request_id).is_ok());
+            assert!(worker_pool.cancel(request_id).is_err());
+            assert!(worker_pool.cancel(request_id + 1).is_err());
+        }
+
+        let worker_pool = WorkerPool::new(parameter_packet.client.clone(),
+                                          parameter_packet.directory_cache.clone(),
+                                          1);
+        let _ = unwrap_result!(worker_pool.submit("{}".to_string(), user_data, callback));
+        let (result, _) = unwrap_result!(rx.recv());
+        let cancelled: i32 = FfiError::RequestCancelled.into();
+        assert!(result != 0);
+        assert!(result != cancelled);
+    }
+}